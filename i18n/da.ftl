@@ -7,21 +7,21 @@ options =
     Options:
         -c                        Execute COMMAND and exit
         -i                        Force interactive mode
-        -l, --login               unsuported
+        -l, --login               Act as a login shell
         -r                        unsuported
-        -s                        unsuported
+        -s                        Read commands from standard input
         -D                        unsuported
-        -O, +O                    unsuported
-        --                        unsuported
+        -O, +O                    Set or unset a shopt option
+        --                        End of options
         --debugger                unsuported
         --dimp-po-strings         unsuported
         --help                    Display this help message and exit
-        --init-file FILE          unsuported
-        --rcfile FILE             unsuported
+        --init-file FILE          Same as --rcfile
+        --rcfile FILE             Read FILE instead of ~/.sushrc
         --noediting               unsuported
-        --noprofile               unsuported
-        --norc                    unsuported
-        --posix                   unsuported
+        --noprofile               Do not read the login profile files
+        --norc                    Do not read ~/.sushrc
+        --posix                   Enable POSIX mode
         --restricted              unsuported
         -v, --verbose             unsuported
         --version                 Display version information and exit
@@ -55,47 +55,48 @@ builtins =
         compopt                   Unsupported
         continue                  Resume the next iteration of a loop
         declare                   Unsupported
-        dirs                      Unsupported
-        disown                    Unsupported
+        dirs                      Display the directory stack
+        disown                    Remove jobs from the job table
         echo                      Unsupported
-        enable                    Unsupported
+        enable                    Enable and disable shell builtins
         eval                      Evaluate arguments as a shell command
-        exec                      Unsupported
+        exec                      Replace the shell with the given command
         exit                      Exit the shell
         export                    Unsupported
         false                     Do nothing, unsuccessfully
         fc                        Unsupported
         fg                        Resume a job in the foreground
         getopts                   Parse positional parameters
-        hash                      Unsupported
-        help                      Unsupported
+        hash                      Remember or display program locations
+        help                      Display information about builtin commands
         history                   Show or manipulate the command history
         jobs                      Display status of jobs
-        kill                      Unsupported
+        kill                      Send a signal to processes or jobs
         let                       Unsupported
         local                     Declare local variables inside functions
         logout                    Unsupported
-        mapfile                   Unsupported
-        popd                      Unsupported
-        printf                    Unsupported
-        pushd                     Unsupported
+        mapfile                   Read lines into an indexed array
+        popd                      Remove directories from the stack
+        printf                    Format and print arguments
+        pushd                     Add directories to the stack
         pwd                       Print the current working directory
         read                      Read a line from standard input
+        readarray                 Read lines into an indexed array
         readonly                  Unsupported
         return                    Return from a shell function
         set                       Modify shell options
         shift                     Shift positional parameters
         shopt                     Change shell optional behavior
         source                    Read and execute commands from a file
-        suspend                   Unsupported
+        suspend                   Suspend the shell
         test                      Unsupported
-        times                     Unsupported
-        trap                      Unsupported
+        times                     Display process times
+        trap                      Trap signals and other events
         true                      Do nothing, successfully
-        type                      Unsupported
+        type                      Display information about command type
         typeset                   Unsupported
         ulimit                    Unsupported
-        umask                     Unsupported
+        umask                     Set the file creation mask
         unalias                   Remove aliases
         unset                     Unset variables or functions
         wait                      Wait for jobs to complete
@@ -164,7 +165,7 @@ variables-bash =
         BASH_CMDS                 unsuported
         BASH_COMMAND              unsuported
         BASH_COMPAT               unsuported
-        BASH_ENV                  unsuported
+        BASH_ENV                  Startup file for non-interactive shells
         BASH_EXECUTION_STRING     unsuported
         BASH_LINENO               unsuported
         BASH_LOADABLES_PATH       unsuported
@@ -187,7 +188,7 @@ variables-bash =
         COPROC                    unsuported
         DIRSTACK                  unsuported
         EMACS                     unsuported
-        ENV                       unsuported
+        ENV                       Startup file in POSIX mode
         EPOCHREALTIME             Epoch seconds with microseconds
         EPOCHSECONDS              Epoch seconds (integer)
         EUID                      unsuported
@@ -7,21 +7,21 @@ options =
     Options:
         -c                        Execute COMMAND and exit
         -i                        Force interactive mode
-        -l, --login               unsuported
+        -l, --login               Act as a login shell
         -r                        unsuported
-        -s                        unsuported
+        -s                        Read commands from standard input
         -D                        unsuported
-        -O, +O                    unsuported
-        --                        unsuported
+        -O, +O                    Set or unset a shopt option
+        --                        End of options
         --debugger                unsuported
         --dimp-po-strings         unsuported
         --help                    Display this help message and exit
-        --init-file FILE          unsuported
-        --rcfile FILE             unsuported
+        --init-file FILE          Same as --rcfile
+        --rcfile FILE             Read FILE instead of ~/.sushrc
         --noediting               unsuported
-        --noprofile               unsuported
-        --norc                    unsuported
-        --posix                   unsuported
+        --noprofile               Do not read the login profile files
+        --norc                    Do not read ~/.sushrc
+        --posix                   Enable POSIX mode
         --restricted              unsuported
         -v, --verbose             unsuported
         --version                 Display version information and exit
@@ -55,47 +55,48 @@ builtins =
         compopt                   Unsupported
         continue                  Resume the next iteration of a loop
         declare                   Unsupported
-        dirs                      Unsupported
-        disown                    Unsupported
+        dirs                      Display the directory stack
+        disown                    Remove jobs from the job table
         echo                      Unsupported
-        enable                    Unsupported
+        enable                    Enable and disable shell builtins
         eval                      Evaluate arguments as a shell command
-        exec                      Unsupported
+        exec                      Replace the shell with the given command
         exit                      Exit the shell
         export                    Unsupported
         false                     Do nothing, unsuccessfully
         fc                        Unsupported
         fg                        Resume a job in the foreground
         getopts                   Parse positional parameters
-        hash                      Unsupported
-        help                      Unsupported
+        hash                      Remember or display program locations
+        help                      Display information about builtin commands
         history                   Show or manipulate the command history
         jobs                      Display status of jobs
-        kill                      Unsupported
+        kill                      Send a signal to processes or jobs
         let                       Unsupported
         local                     Declare local variables inside functions
         logout                    Unsupported
-        mapfile                   Unsupported
-        popd                      Unsupported
-        printf                    Unsupported
-        pushd                     Unsupported
+        mapfile                   Read lines into an indexed array
+        popd                      Remove directories from the stack
+        printf                    Format and print arguments
+        pushd                     Add directories to the stack
         pwd                       Print the current working directory
         read                      Read a line from standard input
+        readarray                 Read lines into an indexed array
         readonly                  Unsupported
         return                    Return from a shell function
         set                       Modify shell options
         shift                     Shift positional parameters
         shopt                     Change shell optional behavior
         source                    Read and execute commands from a file
-        suspend                   Unsupported
+        suspend                   Suspend the shell
         test                      Unsupported
-        times                     Unsupported
-        trap                      Unsupported
+        times                     Display process times
+        trap                      Trap signals and other events
         true                      Do nothing, successfully
-        type                      Unsupported
+        type                      Display information about command type
         typeset                   Unsupported
         ulimit                    Unsupported
-        umask                     Unsupported
+        umask                     Set the file creation mask
         unalias                   Remove aliases
         unset                     Unset variables or functions
         wait                      Wait for jobs to complete
@@ -143,7 +144,7 @@ variables-bash =
         BASH_CMDS                 unsuported
         BASH_COMMAND              unsuported
         BASH_COMPAT               unsuported
-        BASH_ENV                  unsuported
+        BASH_ENV                  Startup file for non-interactive shells
         BASH_EXECUTION_STRING     unsuported
         BASH_LINENO               unsuported
         BASH_LOADABLES_PATH       unsuported
@@ -166,7 +167,7 @@ variables-bash =
         COPROC                    unsuported
         DIRSTACK                  unsuported
         EMACS                     unsuported
-        ENV                       unsuported
+        ENV                       Startup file in POSIX mode
         EPOCHREALTIME             Epoch seconds with microseconds
         EPOCHSECONDS              Epoch seconds (integer)
         EUID                      unsuported
@@ -243,3 +244,903 @@ text-version =
 
     THIS SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
     EXPRESS OR IMPLIED, TO THE EXTENT PERMITTED BY LAW.
+
+## help builtin
+## Each entry has the synopsis as its value and the description in `.desc`.
+## The first line of `.desc` is used by `help -d`. Entries missing from
+## a translation are taken from this file.
+
+help-header =
+    These shell commands are defined internally.  Type `help' to see this list.
+    Type `help name' to find out more about the function `name'.
+
+    A star (*) next to a name means that the command is disabled.
+
+help-matching = Shell commands matching keyword
+
+help-colon = :
+    .desc =
+        Do nothing.
+
+        Only the expansions of the arguments take effect.
+
+        Exit Status:
+        Always succeeds.
+
+help-dot = . filename [arguments]
+    .desc =
+        Execute commands from a file in the current shell.
+
+        Read and execute the commands in FILENAME. If FILENAME contains no
+        slash, it is searched in PATH. ARGUMENTS become the positional
+        parameters while FILENAME is executed.
+
+        Exit Status:
+        Returns the status of the last command in FILENAME, or failure if
+        FILENAME cannot be read.
+
+help-bracket = [ arg... ]
+    .desc =
+        Evaluate a conditional expression.
+
+        The same as the `test' builtin except that the last argument must be
+        a literal `]'.
+
+help-double-bracket = [[ expression ]]
+    .desc =
+        Evaluate a conditional expression.
+
+        EXPRESSION is made of the primaries of the `test' builtin and the
+        following operators. Words are not split and pathnames are not
+        expanded inside the brackets.
+
+          ( EXPRESSION )        the value of EXPRESSION
+          ! EXPRESSION          true if EXPRESSION is false
+          EXPR1 && EXPR2        true if both are true
+          EXPR1 || EXPR2        true if either is true
+          STRING == PATTERN     true if STRING matches the glob PATTERN
+          STRING != PATTERN     true if STRING doesn't match PATTERN
+          STRING =~ REGEX       true if STRING matches the extended regular
+                                expression REGEX; the captures are stored
+                                in BASH_REMATCH
+
+        Exit Status:
+        0 or 1 depending on EXPRESSION, 2 on a syntax error.
+
+help-brace = {"{"} COMMANDS ; {"}"}
+    .desc =
+        Group commands.
+
+        Run COMMANDS in the current shell as one unit. The redirections
+        given to the group apply to all the commands in it.
+
+        Exit Status:
+        Returns the status of the last command executed.
+
+help-alias = alias [-p] [name[=value] ... ]
+    .desc =
+        Define or display aliases.
+
+        Without arguments or with -p, print all aliases in a reusable form.
+        NAME=VALUE defines an alias. A NAME alone prints its definition.
+
+        Exit Status:
+        Returns success unless a NAME has no alias.
+
+help-bg = bg [job_spec ...]
+    .desc =
+        Resume jobs in the background.
+
+        Continue each stopped JOB_SPEC as a background job. Without
+        JOB_SPEC, the current job is used.
+
+        Exit Status:
+        Returns success unless job control is off or an error occurs.
+
+help-bind = bind [arg ...]
+    .desc =
+        Accept readline key bindings.
+
+        Sush has its own line editor, which doesn't read readline
+        settings. The command exists so that startup files written for
+        Bash keep working and ignores all its arguments.
+
+        Exit Status:
+        Always succeeds.
+
+help-break = break [n]
+    .desc =
+        Exit for, while or repeat loops.
+
+        Leave the innermost loop, or N enclosing loops.
+
+        Exit Status:
+        Returns success unless N is less than 1.
+
+help-builtin = builtin [shell-builtin [arg ...]]
+    .desc =
+        Execute a shell builtin.
+
+        Run SHELL-BUILTIN with ARGs even if a function of the same name
+        exists.
+
+        Exit Status:
+        Returns the status of SHELL-BUILTIN, or failure if it is not a
+        shell builtin.
+
+help-caller = caller [expr]
+    .desc =
+        Print the context of the current function call.
+
+        Without EXPR, print the line number and the file of the caller.
+        With EXPR, print the line number, the function name and the file
+        of the EXPR-th frame of the call stack.
+
+        Exit Status:
+        Returns failure outside functions or when EXPR is invalid.
+
+help-case = case WORD in [PATTERN [| PATTERN]...) COMMANDS ;;]... esac
+    .desc =
+        Execute commands chosen by pattern matching.
+
+        Run the COMMANDS of the first PATTERN that matches WORD. A list
+        terminated with `;&' falls through to the next list, and one
+        terminated with `;;&' continues the matching.
+
+        Exit Status:
+        Returns the status of the last command executed.
+
+help-cd = cd [-L|[-P [-e]] [-@]] [dir]
+    .desc =
+        Change the current directory.
+
+        Change the current directory to DIR, or to HOME without DIR.
+        `cd -' changes to OLDPWD and prints it. PWD and OLDPWD are updated.
+
+        The directories in CDPATH, separated by colons, are searched for
+        a DIR not beginning with `/', `./' or `../'. The directory is printed
+        when it is found through a nonempty entry of CDPATH. With the
+        cdable_vars shell option, a DIR which can't be changed to is taken
+        as the name of a variable holding the directory. With cdspell, a
+        misspelled DIR is corrected in interactive shells.
+
+        Options:
+          -L    follow symbolic links, resolving `..' before them (default)
+          -P    use the physical directory structure without symbolic links
+          -e    with -P, fail when the current directory can't be determined
+
+        `set -P' makes -P the default.
+
+        Exit Status:
+        Returns success if the directory is changed, and with -e, if PWD
+        is set successfully.
+
+help-command = command [-pVv] command [arg ...]
+    .desc =
+        Execute a command ignoring shell functions.
+
+        Run COMMAND with ARGs, looking for a builtin or a file in PATH
+        without calling a function of the same name.
+
+        Options:
+          -p    use the default PATH which finds all the standard utilities
+          -v    print the word or the path used to invoke COMMAND
+          -V    print a description of COMMAND
+
+        Exit Status:
+        Returns the status of COMMAND, or failure if it is not found.
+
+help-compgen = compgen [-abcdefhjouv] [-A action] [-G globpat] [-W wordlist] [-P prefix] [-S suffix] [-X filterpat] [word]
+    .desc =
+        Print possible completions.
+
+        Print the candidates selected by the options that start with WORD.
+        The options are the same as those of `complete'. -h lists the
+        commands in the history.
+
+        Exit Status:
+        Returns success unless an invalid option is given.
+
+help-complete = complete [-abcdefgjkouv] [-p] [-A action] [-F function] [-W wordlist] [-P prefix] [-S suffix] [name ...]
+    .desc =
+        Specify how arguments are completed.
+
+        Register the completion of the arguments of each NAME. Without
+        options or with -p, print the existing specifications.
+
+        Exit Status:
+        Returns success unless an invalid option is given.
+
+help-compopt = compopt [-o|+o option] [-DE] [name ...]
+    .desc =
+        Modify completion options.
+
+        Turn the completion OPTIONs of each NAME, or of the default (-D)
+        or empty line (-E) completion, on with -o or off with +o.
+
+        Exit Status:
+        Returns success unless an invalid option is given.
+
+help-continue = continue [n]
+    .desc =
+        Resume for, while or repeat loops.
+
+        Start the next iteration of the innermost loop, or of the N-th
+        enclosing loop.
+
+        Exit Status:
+        Returns success unless N is less than 1.
+
+help-coproc = coproc [NAME] command [redirections]
+    .desc =
+        Create a coprocess.
+
+        Run COMMAND asynchronously with its standard output and input
+        connected through pipes to the file descriptors in NAME[0] and
+        NAME[1]. NAME defaults to COPROC.
+
+        Exit Status:
+        Always succeeds.
+
+help-debug = debug
+    .desc =
+        Do nothing.
+
+        A hook for debugging sush itself.
+
+        Exit Status:
+        Always succeeds.
+
+help-declare = declare [-aAfFgilnrux] [-p] [name[=value] ...]
+    .desc =
+        Set variable values and attributes.
+
+        Without NAMEs, print the variables having the given attributes.
+
+        Options:
+          -f    use or print functions only
+          -F    print the function names only
+          -g    create global variables inside functions
+          -p    print the attributes and values of NAMEs
+
+        Attributes:
+          -a    indexed array
+          -A    associative array
+          -i    integer
+          -l    convert to lower case on assignment
+          -u    convert to upper case on assignment
+          -n    reference to the variable named by the value
+          -r    read-only
+          -x    export
+
+        Inside functions, the variables are local unless -g is given.
+
+        Exit Status:
+        Returns success unless an invalid option or assignment is given.
+
+help-dirs = dirs [-clpv] [+N] [-N]
+    .desc =
+        Display the directory stack.
+
+        Options:
+          -c    clear the stack
+          -l    do not abbreviate the home directory with `~'
+          -p    print one entry per line
+          -v    print one entry per line with its position
+
+        +N and -N print the N-th entry from the left or right.
+
+        Exit Status:
+        Returns success unless an invalid option is given.
+
+help-disown = disown [-h] [-ar] [jobspec ... | pid ...]
+    .desc =
+        Remove jobs from the job table.
+
+        Options:
+          -a    remove all jobs
+          -h    keep the jobs but don't send SIGHUP to them
+          -r    remove only the running jobs
+
+        Exit Status:
+        Returns success unless a JOBSPEC is invalid.
+
+help-do = do COMMANDS; done
+    .desc =
+        Delimit the body of a loop.
+
+        `do' and `done' enclose the COMMANDS run on each iteration of for,
+        while and repeat loops.
+
+help-echo = echo [-neE] [arg ...]
+    .desc =
+        Print the arguments.
+
+        Print ARGs separated by spaces and followed by a newline.
+
+        Options:
+          -n    omit the trailing newline
+          -e    interpret backslash escapes
+          -E    don't interpret backslash escapes
+
+        Exit Status:
+        Returns success unless a write error occurs.
+
+help-enable = enable [-a] [-dnps] [-f filename] [name ...]
+    .desc =
+        Enable and disable shell builtins.
+
+        Options:
+          -a    print all builtins with their states
+          -n    disable NAMEs, or print the disabled builtins
+          -p    print the builtins in a reusable form
+          -s    print the POSIX special builtins only
+          -f    load NAMEs from the shared object FILENAME
+          -d    remove builtins loaded with -f
+
+        Exit Status:
+        Returns success unless NAME is not a builtin or an error occurs.
+
+help-eval = eval [arg ...]
+    .desc =
+        Execute the arguments as a shell command.
+
+        Join ARGs with spaces and run the result in the current shell.
+
+        Exit Status:
+        Returns the status of the command, or success if it is empty.
+
+help-exec = exec [-cl] [-a name] [command [argument ...]] [redirection ...]
+    .desc =
+        Replace the shell with a command.
+
+        Run COMMAND in place of the shell. Without COMMAND, the
+        redirections take effect in the current shell and are inherited
+        by the commands run later.
+
+        Options:
+          -a name    pass NAME as the zeroth argument to COMMAND
+          -c         execute COMMAND with an empty environment
+          -l         place a dash in the zeroth argument to COMMAND
+
+        If the command cannot be executed, a non-interactive shell exits,
+        unless the shell option `execfail' is set.
+
+        Exit Status:
+        Returns success unless COMMAND is not found or a redirection fails.
+
+help-exit = exit [n]
+    .desc =
+        Exit the shell.
+
+        Exit with the status N, or with the status of the last command.
+
+help-export = export [name[=value] ...]
+    .desc =
+        Set the export attribute of variables.
+
+        Mark each NAME for export to the environment of executed commands
+        and assign VALUE if given.
+
+        Exit Status:
+        Returns success unless an invalid NAME is given.
+
+help-false = false
+    .desc =
+        Return an unsuccessful result.
+
+        Exit Status:
+        Always fails.
+
+help-fg = fg [job_spec]
+    .desc =
+        Move a job to the foreground.
+
+        Make JOB_SPEC, or the current job, the foreground job.
+
+        Exit Status:
+        Returns the status of the job, or failure on an error.
+
+help-for = for NAME [in WORDS ... ] ; do COMMANDS; done
+    .desc =
+        Execute commands for each item of a list.
+
+        Run COMMANDS with NAME set to each of WORDS in turn. Without
+        `in WORDS', the positional parameters are used.
+
+        The arithmetic form `for (( EXP1; EXP2; EXP3 )); do COMMANDS; done'
+        evaluates EXP1 once, then runs COMMANDS and evaluates EXP3 while
+        EXP2 is not zero.
+
+        Exit Status:
+        Returns the status of the last command executed.
+
+help-getopts = getopts optstring name [arg ...]
+    .desc =
+        Parse option arguments.
+
+        Store the next option found in ARGs, or in the positional
+        parameters, into NAME, and its argument into OPTARG. OPTIND is the
+        index of the next argument. A letter followed by a colon in
+        OPTSTRING takes an argument.
+
+        Exit Status:
+        Returns success while an option is found, failure at the end of
+        the options.
+
+help-hash = hash [-lr] [-p pathname] [-dt] [name ...]
+    .desc =
+        Remember or display the locations of commands.
+
+        Without arguments, print the remembered commands with their hit
+        counts. Each NAME is searched in PATH and remembered. The table is
+        emptied whenever PATH is changed.
+
+        Options:
+          -d    forget the location of each NAME
+          -l    print in a form reusable as input
+          -p PATHNAME
+                remember PATHNAME as the location of NAME
+          -r    forget all the locations
+          -t    print the remembered location of each NAME, preceded by
+                the NAME if several NAMEs are given
+
+        With the checkhash shell option, a remembered location which is
+        no longer executable is searched in PATH again.
+
+        Exit Status:
+        Returns success unless NAME is not found or an invalid option is
+        given.
+
+help-help = help [-dms] [pattern ...]
+    .desc =
+        Display information about builtin commands.
+
+        Print the help of the builtins and keywords matching PATTERN, or
+        the list of all topics without PATTERN.
+
+        Options:
+          -d    print a short description of each topic
+          -m    print in a pseudo-manpage format
+          -s    print only the synopsis of each topic
+
+        Exit Status:
+        Returns success unless no topic matches or an invalid option is
+        given.
+
+help-history = history [-c]
+    .desc =
+        Display or clear the command history.
+
+        Print the history with line numbers. -c clears it.
+
+        Exit Status:
+        Returns success unless an invalid option is given.
+
+help-if = if COMMANDS; then COMMANDS; [ elif COMMANDS; then COMMANDS; ]... [ else COMMANDS; ] fi
+    .desc =
+        Execute commands conditionally.
+
+        Run the `then' part of the first `if' or `elif' whose COMMANDS
+        succeed, or the `else' part when none of them succeeds.
+
+        Exit Status:
+        Returns the status of the last command executed, or success when
+        no part is run.
+
+help-jobs = jobs [-lnprs] [jobspec ...] or jobs -x command [args]
+    .desc =
+        Display the status of jobs.
+
+        Options:
+          -l    print the process IDs too
+          -n    print only the jobs changed since the last notification
+          -p    print only the process IDs
+          -r    print only the running jobs
+          -s    print only the stopped jobs
+
+        With -x, COMMAND is run after each JOBSPEC in ARGS is replaced
+        with its process group ID.
+
+        Exit Status:
+        Returns success unless an invalid option or JOBSPEC is given.
+
+help-kill = kill [-s sigspec | -n signum | -sigspec] pid | jobspec ... or kill -l [sigspec]
+    .desc =
+        Send a signal to jobs or processes.
+
+        Send SIGSPEC or SIGNUM, or SIGTERM by default, to each PID or
+        JOBSPEC. -l lists the signal names, or converts between names and
+        numbers.
+
+        Exit Status:
+        Returns success unless an invalid argument is given.
+
+help-let = let arg [arg ...]
+    .desc =
+        Evaluate arithmetic expressions.
+
+        Evaluate each ARG as an arithmetic expression.
+
+        Exit Status:
+        Returns failure if the last ARG evaluates to 0, success otherwise.
+
+help-local = local [option] name[=value] ...
+    .desc =
+        Define local variables.
+
+        Create variables visible only in the current function and the
+        functions it calls. The options are the same as those of
+        `declare'.
+
+        Exit Status:
+        Returns failure outside functions or on an invalid assignment.
+
+help-mapfile = mapfile [-d delim] [-n count] [-O origin] [-s count] [-t] [-u fd] [-C callback] [-c quantum] [array]
+    .desc =
+        Read lines into an indexed array.
+
+        Read lines from the standard input into ARRAY, or MAPFILE.
+
+        Options:
+          -d DELIM      end lines with DELIM instead of a newline
+          -n COUNT      read at most COUNT lines
+          -O ORIGIN     start assigning at index ORIGIN
+          -s COUNT      skip the first COUNT lines
+          -t            remove the delimiter from each line
+          -u FD         read from FD
+          -C CALLBACK   evaluate CALLBACK every QUANTUM lines
+          -c QUANTUM    the interval of CALLBACK (5000 by default)
+
+        Exit Status:
+        Returns success unless an invalid option is given or ARRAY is
+        read-only.
+
+help-popd = popd [-n] [+N | -N]
+    .desc =
+        Remove a directory from the directory stack.
+
+        Remove the top entry and change to the new top directory. +N or -N
+        removes the N-th entry from the left or right instead. -n doesn't
+        change the current directory.
+
+        Exit Status:
+        Returns success unless an invalid argument is given or the
+        directory change fails.
+
+help-printf = printf [-v var] format [arguments]
+    .desc =
+        Format and print arguments.
+
+        Print ARGUMENTS following FORMAT like printf(3). FORMAT is reused
+        until all ARGUMENTS are consumed. -v VAR assigns the output to VAR
+        instead of printing it.
+
+        Besides the conversions of printf(3), the following are available.
+          %b        expand the backslash escapes in the argument
+          %q        quote the argument to be reused as shell input
+          %Q        like %q, but the precision applies before quoting
+          %(fmt)T   format the time with strftime(3); the argument is
+                    seconds since the epoch, -1 for now or -2 for the
+                    time the shell started
+
+        Exit Status:
+        Returns success unless an invalid option is given or an error
+        occurs.
+
+help-pushd = pushd [-n] [+N | -N | dir]
+    .desc =
+        Add a directory to the directory stack.
+
+        Push the current directory and change to DIR. Without arguments,
+        the top two entries are exchanged. +N or -N rotates the stack so
+        that the N-th entry comes to the top. -n doesn't change the
+        current directory.
+
+        Exit Status:
+        Returns success unless an invalid argument is given or the
+        directory change fails.
+
+help-pwd = pwd [-LP]
+    .desc =
+        Print the current working directory.
+
+        Options:
+          -L    print the value of PWD (default)
+          -P    print the physical directory without symbolic links
+
+        Exit Status:
+        Returns success unless an invalid option is given.
+
+help-read = read [-ers] [-a array] [-d delim] [-i text] [-n nchars] [-N nchars] [-p prompt] [-t timeout] [-u fd] [name ...]
+    .desc =
+        Read a line from the standard input.
+
+        Split the line with IFS and assign the words to NAMEs. The last
+        NAME receives the rest of the line. Without NAMEs, the line is
+        stored in REPLY.
+
+        Options:
+          -a ARRAY      assign the words to the indexed array ARRAY
+          -d DELIM      read until DELIM instead of a newline
+          -e            edit the line with the line editor
+          -i TEXT       start the edited line with TEXT
+          -n NCHARS     return after NCHARS characters or a delimiter
+          -N NCHARS     return after exactly NCHARS characters
+          -p PROMPT     print PROMPT to the terminal before reading
+          -r            don't treat backslashes as escapes
+          -s            don't echo the input on a terminal
+          -t TIMEOUT    fail after TIMEOUT seconds
+          -u FD         read from FD
+
+        Exit Status:
+        Returns success unless the end of the file is reached, the read
+        times out (greater than 128) or an error occurs.
+
+help-readarray = readarray [-d delim] [-n count] [-O origin] [-s count] [-t] [-u fd] [-C callback] [-c quantum] [array]
+    .desc =
+        Read lines into an indexed array.
+
+        The same as `mapfile'.
+
+help-readonly = readonly [-aA] [name[=value] ...]
+    .desc =
+        Mark variables read-only.
+
+        Make each NAME read-only after assigning VALUE if given. Without
+        NAMEs, print the read-only variables.
+
+        Exit Status:
+        Returns success unless an invalid NAME is given.
+
+help-repeat = repeat N COMMAND
+    .desc =
+        Execute a command a number of times.
+
+        Run COMMAND, which is usually a group like `do ... done', N times.
+        This command is an extension of sush.
+
+        Exit Status:
+        Returns the status of the last command executed.
+
+help-return = return [n]
+    .desc =
+        Return from a shell function.
+
+        Leave the current function or sourced file with the status N, or
+        with the status of the last command.
+
+        Exit Status:
+        Returns N, or failure outside functions and sourced files.
+
+help-set = set [-abefhkmnptuvxBCEHPT] [-o option-name] [--] [-] [arg ...]
+    .desc =
+        Set shell options and positional parameters.
+
+        `-' followed by an option letter turns it on and `+' turns it off.
+        `-o OPTION-NAME' does the same with a long name. Without arguments,
+        print the shell variables. `set -o' prints the options. The ARGs
+        left after the options become the positional parameters.
+
+        Exit Status:
+        Returns success unless an invalid option is given.
+
+help-shift = shift [n]
+    .desc =
+        Shift the positional parameters.
+
+        Remove the first N positional parameters, or the first one.
+
+        Exit Status:
+        Returns success unless N is negative or greater than $#.
+
+help-shopt = shopt [-pqsu] [-o] [optname ...]
+    .desc =
+        Set and unset shell options.
+
+        Without options, print the state of OPTNAMEs or of all options.
+
+        Options:
+          -o    use the options of `set -o'
+          -p    print in a reusable form
+          -q    print nothing and return the state
+          -s    turn OPTNAMEs on
+          -u    turn OPTNAMEs off
+
+        Exit Status:
+        Returns success if OPTNAME is on, failure if it is off or invalid.
+
+help-source = source filename [arguments]
+    .desc =
+        Execute commands from a file in the current shell.
+
+        The same as `.'.
+
+help-suspend = suspend [-f]
+    .desc =
+        Suspend the shell.
+
+        Stop the shell until it receives SIGCONT. A login shell is
+        suspended only with -f.
+
+        Exit Status:
+        Returns success unless job control is off or an error occurs.
+
+help-test = test [expr]
+    .desc =
+        Evaluate a conditional expression.
+
+        Return success or failure by evaluating EXPR. The number of
+        arguments decides the meaning as POSIX specifies.
+
+        File operators:
+          -a FILE, -e FILE      FILE exists
+          -b, -c, -d, -f, -p, -S FILE
+                                FILE is a block device, a character device,
+                                a directory, a regular file, a FIFO or a
+                                socket
+          -h FILE, -L FILE      FILE is a symbolic link
+          -g, -u, -k FILE       FILE has set-group-id, set-user-id or the
+                                sticky bit
+          -r, -w, -x FILE       FILE is readable, writable or executable
+          -s FILE               FILE is not empty
+          -t FD                 FD is a terminal
+          -G, -O FILE           FILE is owned by the group or user
+          -N FILE               FILE was modified since it was last read
+          FILE1 -nt FILE2       FILE1 is newer than FILE2
+          FILE1 -ot FILE2       FILE1 is older than FILE2
+          FILE1 -ef FILE2       both are the same file
+
+        String operators:
+          -z STRING, -n STRING  STRING is empty or not empty
+          STRING                STRING is not empty
+          S1 = S2, S1 != S2     the strings are equal or not
+          S1 < S2, S1 > S2      S1 sorts before or after S2
+
+        Other operators:
+          -o OPTION             the shell option OPTION is on
+          -v VAR                VAR is set
+          -R VAR                VAR is a name reference
+          ! EXPR                EXPR is false
+          EXPR1 -a EXPR2        both are true
+          EXPR1 -o EXPR2        either is true
+          ARG1 OP ARG2          integer comparison with OP one of -eq,
+                                -ne, -lt, -le, -gt and -ge
+
+        Exit Status:
+        Returns success if EXPR is true, failure if it is false, and 2 on
+        an error.
+
+help-times = times
+    .desc =
+        Display process times.
+
+        Print the user and system times of the shell and of its children.
+
+        Exit Status:
+        Always succeeds.
+
+help-trap = trap [-lpP] [[action] signal_spec ...]
+    .desc =
+        Trap signals and other events.
+
+        Run ACTION when the shell receives SIGNAL_SPEC. An empty ACTION
+        ignores the signals and `-' restores the default behavior. EXIT is
+        trapped when the shell exits and DEBUG before each command.
+
+        Options:
+          -l    list the signal names and numbers
+          -p    print the traps in a reusable form
+          -P    print only the actions of the traps
+
+        Exit Status:
+        Returns success unless an invalid SIGNAL_SPEC or option is given.
+
+help-true = true
+    .desc =
+        Return a successful result.
+
+        Exit Status:
+        Always succeeds.
+
+help-type = type [-afptP] name [name ...]
+    .desc =
+        Display how each name would be interpreted as a command.
+
+        Options:
+          -a    print every alias, keyword, function, builtin and file in
+                PATH named NAME instead of the first one
+          -f    don't look up shell functions
+          -p    print the path of the file that would be executed
+          -P    search PATH even if NAME is an alias, builtin or function
+          -t    print one of `alias', `keyword', `function', `builtin' or
+                `file'
+
+        Exit Status:
+        Returns success if all NAMEs are found.
+
+help-typeset = typeset [-aAfFgilnrux] [-p] [name[=value] ...]
+    .desc =
+        Set variable values and attributes.
+
+        The same as `declare'.
+
+help-ulimit = ulimit [-SHa] [-cdefilmnpqrstuvxR] [limit]
+    .desc =
+        Modify the resource limits.
+
+        Print or set the limit of the resource selected by the option,
+        or of the file size without an option. LIMIT is a number,
+        `unlimited', `soft' or `hard'.
+
+        Options:
+          -S    use the soft limit
+          -H    use the hard limit
+          -a    print all limits
+
+        Exit Status:
+        Returns success unless an invalid option is given or an error
+        occurs.
+
+help-umask = umask [-p] [-S] [mode]
+    .desc =
+        Display or set the file mode creation mask.
+
+        Set the mask to MODE, an octal number or a symbolic mode like
+        `u=rwx,g=rx,o='. Without MODE, print the mask.
+
+        Options:
+          -p    print in a reusable form
+          -S    print in the symbolic form
+
+        Exit Status:
+        Returns success unless MODE is invalid or an invalid option is
+        given.
+
+help-unalias = unalias [-a] name [name ...]
+    .desc =
+        Remove aliases.
+
+        Remove each NAME from the aliases, or all aliases with -a.
+
+        Exit Status:
+        Returns success unless a NAME is not an alias.
+
+help-unset = unset [-f] [-v] [-n] [name ...]
+    .desc =
+        Unset variables and functions.
+
+        Options:
+          -f    unset functions
+          -v    unset variables
+          -n    unset name references themselves
+
+        Without options, a variable is tried first and then a function.
+
+        Exit Status:
+        Returns success unless an invalid option is given or a NAME is
+        read-only.
+
+help-wait = wait [-fn] [-p var] [id ...]
+    .desc =
+        Wait for jobs to finish.
+
+        Wait for each ID, a process ID or a job spec, or for all the
+        background jobs without ID.
+
+        Options:
+          -f    wait until the jobs terminate, not only stop
+          -n    wait for the next job to finish
+          -p    assign the ID of the finished job to VAR
+
+        Exit Status:
+        Returns the status of the last ID.
+
+help-while = while COMMANDS; do COMMANDS; done
+    .desc =
+        Execute commands while a test succeeds.
+
+        Repeat the body while the last command of COMMANDS succeeds.
+
+        Exit Status:
+        Returns the status of the last command executed.
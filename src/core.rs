@@ -10,17 +10,21 @@ pub mod jobtable;
 pub mod options;
 mod file_descs;
 
+use self::builtins::enable::Loadable;
+use self::builtins::trap;
 use self::completion::{Completion, CompletionEntry};
 use self::database::DataBase;
 use self::options::Options;
 use self::file_descs::FileDescriptors;
 use crate::core::jobtable::JobEntry;
 use crate::elements::substitution::Substitution;
+use crate::utils::clock;
 use crate::{error, proc_ctrl, signal};
 use nix::sys::signal::Signal;
 use nix::sys::time::{TimeSpec, TimeVal};
+use nix::time::{clock_gettime, ClockId};
 use nix::unistd::Pid;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::os::fd::RawFd;
 //use std::os::fd::{FromRawFd, OwnedFd};
 use std::sync::atomic::AtomicBool;
@@ -33,6 +37,9 @@ pub struct MeasuredTime {
     pub real: TimeSpec,
     pub user: TimeVal,
     pub sys: TimeVal,
+    pub posix_format: bool,
+    pub null_command: bool,
+    pub shell_start: TimeSpec,
 }
 
 impl Default for MeasuredTime {
@@ -41,6 +48,9 @@ impl Default for MeasuredTime {
             real: TimeSpec::new(0, 0),
             user: TimeVal::new(0, 0),
             sys: TimeVal::new(0, 0),
+            posix_format: false,
+            null_command: false,
+            shell_start: clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap(),
         }
     }
 }
@@ -56,9 +66,13 @@ pub struct ShellCore {
     pub history: Vec<String>,
     pub builtins: HashMap<String, BuiltinFn>,
     pub subst_builtins: HashMap<String, SubstBuiltinFn>,
+    pub disabled_builtins: HashMap<String, BuiltinFn>,
+    pub disabled_subst_builtins: HashMap<String, SubstBuiltinFn>,
+    pub loadables: HashMap<String, Loadable>,
     pub sigint: Arc<AtomicBool>,
-    pub trapped: Vec<(Arc<AtomicBool>, String)>,
-    pub traplist: Vec<(i32, String)>,
+    pub traps: BTreeMap<i32, String>,
+    pub parent_traps: Option<BTreeMap<i32, String>>,
+    pub trap_originals: HashMap<i32, libc::sigaction>,
     pub is_subshell: bool,
     pub source_function_level: i32,
     pub source_files: Vec<String>,
@@ -74,6 +88,7 @@ pub struct ShellCore {
     pub job_table: Vec<JobEntry>,
     pub job_table_priority: Vec<usize>,
     current_dir: Option<path::PathBuf>, // the_current_working_directory
+    pub dir_stack: Vec<String>, // DIRSTACK without the current directory
     pub completion: Completion,
     pub measured_time: MeasuredTime,
     pub options: Options,
@@ -82,14 +97,23 @@ pub struct ShellCore {
     pub script_name: String,
     pub exit_script: String,
     pub exit_script_run: bool,
+    pub err_script: String,
+    pub err_trap_running: bool,
+    pub debug_script: String,
+    pub debug_trap_running: bool,
+    pub return_script: String,
+    pub return_trap_running: bool,
+    pub logout_file_run: bool,
     pub valid_assoc_expand_once: bool,
     //pub process_sub: Vec<(Pid, RawFd)>,
     pub proc_sub_pid: Vec<Pid>,
     pub proc_sub_fd: Vec<RawFd>,
+    pub start_time: i64,
 }
 
 impl ShellCore {
     pub fn configure(&mut self) -> Result<(), ExecError> {
+        signal::record_entry_dispositions();
         self.init_current_directory();
         self.set_initial_parameters();
         self.set_builtins();
@@ -98,11 +122,18 @@ impl ShellCore {
 
         let _ = self.db.set_param("PS4", "+ ", None);
 
-        if file_check::is_tty(0) && self.script_name == "-" {
-            self.db.flags += "himH";
+        let forced_interactive = self.db.flags.contains('i');
+        if (file_check::is_tty(0) || forced_interactive) && self.script_name == "-" {
+            for f in "himH".chars() {
+                if !self.db.flags.contains(f) {
+                    self.db.flags.push(f);
+                }
+            }
             let _ = self.db.set_param("PS1", "🍣 ", None);
             let _ = self.db.set_param("PS2", "> ", None);
-            self.tty_fd = Some(self.fds.dupfd_cloexec(0, 255)?);
+            if file_check::is_tty(0) {
+                self.tty_fd = Some(self.fds.dupfd_cloexec(0, 255)?);
+            }
         } else {
             self.db.flags += "h";
         }
@@ -133,16 +164,18 @@ impl ShellCore {
     pub fn new() -> Self {
         ShellCore {
             db: DataBase::new(),
-            sigint: Arc::new(AtomicBool::new(false)),
+            sigint: Arc::clone(&signal::SIGINT),
             options: Options::new_as_basic_opts(),
             shopts: Options::new_as_shopts(),
             script_name: "-".to_string(),
             fds: FileDescriptors::new(),
+            start_time: clock::epoch_seconds(),
             ..Default::default()
         }
     }
 
     pub fn configure_c_mode(&mut self) -> Result<(), ExecError> {
+        signal::record_entry_dispositions();
         if file_check::is_tty(0) {
             self.tty_fd = Some(self.fds.dupfd_cloexec(0, 255)?);
         }
@@ -234,11 +267,23 @@ impl ShellCore {
         signal::restore(Signal::SIGPIPE);
 
         self.is_subshell = true;
+        signal::init_dispatcher_in_subshell(self);
         proc_ctrl::set_pgid(self, pid, pgid);
         let _ = self.set_subshell_parameters();
         //self.job_table.clear();
 
+        signal::reset_traps_in_subshell(self);
         self.exit_script.clear();
+        if !self.options.query("errtrace") {
+            self.err_script.clear();
+            self.traps.remove(&trap::ERR_TRAP);
+        }
+        if !self.options.query("functrace") {
+            self.debug_script.clear();
+            self.return_script.clear();
+            self.traps.remove(&trap::DEBUG_TRAP);
+            self.traps.remove(&trap::RETURN_TRAP);
+        }
     }
 
     pub fn init_current_directory(&mut self) {
@@ -9,31 +9,36 @@ mod command;
 pub mod compgen;
 pub mod complete;
 mod compopt;
+pub mod dirs;
 mod echo;
+pub mod enable;
 mod exec;
 mod getopts;
 mod hash;
+mod help;
 mod history;
 mod job_commands;
 mod loop_control;
+mod mapfile;
 pub mod option;
 pub mod variable;
 mod printf;
 mod pwd;
 mod read;
 pub mod source;
-mod trap;
+pub mod trap;
+mod test;
+mod times;
 mod type_;
 #[cfg(not(target_os = "macos"))]
 mod ulimit;
+mod umask;
 mod unset;
 
 use crate::elements::expr::arithmetic::ArithmeticExpr;
 use crate::error::exec::ExecError;
 use crate::error::parse::ParseError;
 use crate::{exit, Feeder, Script, ShellCore};
-use std::io::Write;
-use std::process::Command;
 
 pub fn error_(exit_status: i32, name: &str, msg: &str, core: &mut ShellCore) -> i32 {
     let shellname = core.db.get_param("0").unwrap();
@@ -50,30 +55,6 @@ pub fn error(exit_status: i32, name: &str, err: &ExecError, core: &mut ShellCore
     error_(exit_status, name, &String::from(err), core)
 }
 
-pub fn run_external(core: &mut ShellCore, args: &[String], err_msg_cond: fn(i32) -> bool) -> i32 {
-    match Command::new(&args[0]).args(args[1..].to_vec()).output() {
-        Ok(com) => {
-            let exit_status = com.status.code().unwrap_or(127);
-            if ! com.stdout.is_empty() {
-                let _ = std::io::stdout().write_all(&com.stdout);
-            }
-            if ! err_msg_cond(exit_status) {
-                return exit_status;
-            }
-
-            let shellname = core.db.get_param("0").unwrap();
-            eprint!("{}: ", &shellname);
-            if ! core.db.flags.contains('i') {
-                let lineno = core.db.get_param("LINENO").unwrap_or("".to_string());
-                eprint!("line {}: ", &lineno);
-            }
-            let _ = std::io::stderr().write_all(&com.stderr);
-            exit_status
-        },
-        _ => 127
-    }
-}
-
 impl ShellCore {
     pub fn set_builtins(&mut self) {
         self.builtins.insert(":".to_string(), true_);
@@ -97,9 +78,11 @@ impl ShellCore {
         self.builtins
             .insert("continue".to_string(), loop_control::continue_);
         self.builtins.insert("debug".to_string(), debug);
+        self.builtins.insert("dirs".to_string(), dirs::dirs);
         self.builtins
             .insert("disown".to_string(), job_commands::disown);
         self.builtins.insert("echo".to_string(), echo::echo);
+        self.builtins.insert("enable".to_string(), enable::enable);
         self.builtins.insert("eval".to_string(), eval);
         self.builtins.insert("exec".to_string(), exec::exec);
         self.builtins.insert("exit".to_string(), exit);
@@ -108,21 +91,31 @@ impl ShellCore {
         self.builtins
             .insert("getopts".to_string(), getopts::getopts);
         self.builtins.insert("hash".to_string(), hash::hash);
+        self.builtins.insert("help".to_string(), help::help);
         self.builtins
             .insert("history".to_string(), history::history);
         self.builtins.insert("jobs".to_string(), job_commands::jobs);
         self.builtins.insert("kill".to_string(), job_commands::kill);
         self.builtins.insert("let".to_string(), let_);
+        self.builtins
+            .insert("mapfile".to_string(), mapfile::mapfile);
+        self.builtins.insert("popd".to_string(), dirs::popd);
         self.builtins.insert("printf".to_string(), printf::printf);
+        self.builtins.insert("pushd".to_string(), dirs::pushd);
         self.builtins.insert("pwd".to_string(), pwd::pwd);
         self.builtins.insert("read".to_string(), read::read);
+        self.builtins
+            .insert("readarray".to_string(), mapfile::mapfile);
         self.builtins
             .insert("return".to_string(), loop_control::return_);
         self.builtins.insert("set".to_string(), option::set);
+        self.builtins.insert("times".to_string(), times::times);
         self.builtins.insert("trap".to_string(), trap::trap);
         self.builtins.insert("type".to_string(), type_::type_);
         self.builtins.insert("shift".to_string(), option::shift);
         self.builtins.insert("shopt".to_string(), option::shopt);
+        self.builtins
+            .insert("suspend".to_string(), job_commands::suspend);
 
         //if file::search_command("ulimit").is_none() {
         #[cfg(not(target_os = "macos"))]
@@ -132,13 +125,14 @@ impl ShellCore {
                     self.builtins.insert("ulimit".to_string(), ulimit_mac::ulimit);
                 }*/
 
+        self.builtins.insert("umask".to_string(), umask::umask);
         self.builtins.insert("unalias".to_string(), alias::unalias);
         self.builtins.insert("unset".to_string(), unset::unset);
         self.builtins.insert("source".to_string(), source::source);
         self.builtins.insert(".".to_string(), source::source);
         self.builtins.insert("true".to_string(), true_);
-        self.builtins.insert("test".to_string(), test);
-        self.builtins.insert("[".to_string(), test);
+        self.builtins.insert("test".to_string(), test::test);
+        self.builtins.insert("[".to_string(), test::test);
         self.builtins.insert("wait".to_string(), job_commands::wait);
 
         self.subst_builtins
@@ -202,6 +196,7 @@ pub fn exit(core: &mut ShellCore, args: &[String]) -> i32 {
             _ => core.db.exit_status = 1,
         }
     }
+    exit::run_logout_file(core);
     exit::normal(core)
 }
 
@@ -253,38 +248,3 @@ pub fn let_(core: &mut ShellCore, args: &[String]) -> i32 {
     core.valid_assoc_expand_once = false;
     last_result
 }
-
-pub fn test(core: &mut ShellCore, args: &[String]) -> i32 {
-    /* difference between the builtin test and the external command */
-    if (args.len() == 5 && args[0] == "[" && args[4] == "]")
-    || (args.len() == 4 && args[0] == "test") {
-        if args[2] == "=" {
-            if args[1] == args[3] {
-                return 0;
-            }else if args[1] != args[3] {
-                return 1;
-            }
-        }
-    }
-
-    run_external(core, args, |es| es > 1)
-    /*
-    /* call the external test command */
-    match Command::new(&args[0]).args(args[1..].to_vec()).output() {
-        Ok(com) => {
-            let exit_status = com.status.code().unwrap_or(127);
-            if exit_status > 1 {
-                let msg = String::from_utf8(com.stderr).unwrap_or("".to_string());
-                let shellname = core.db.get_param("0").unwrap();
-                if core.db.flags.contains('i') {
-                    eprintln!("{}: {}", &shellname, msg);
-                } else {
-                    let lineno = core.db.get_param("LINENO").unwrap_or("".to_string());
-                    eprintln!("{}: line {}: {}", &shellname, &lineno, msg);
-                }
-            }
-            exit_status
-        },
-        _ => 127
-    }*/
-}
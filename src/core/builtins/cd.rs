@@ -2,70 +2,273 @@
 //SPDX-FileCopyrightText: 2023 @caro@mi.shellgei.org
 //SPDX-License-Identifier: BSD-3-Clause
 
-use crate::utils::{arg, file};
-use crate::{error, ShellCore};
+use super::error_;
+use crate::utils::{directory, file_check};
+use crate::ShellCore;
+use nix::errno::Errno;
+use std::env;
+use std::io;
+use std::path::PathBuf;
 
-pub fn cd(core: &mut ShellCore, args: &[String]) -> i32 {
-    if core.db.flags.contains('r') {
-        return super::error_(1, &args[0], "restricted", core);
+const USAGE: &str = "cd: usage: cd [-L|[-P [-e]] [-@]] [dir]";
+
+fn errno_msg(e: &io::Error) -> &'static str {
+    Errno::from_raw(e.raw_os_error().unwrap_or(0)).desc()
+}
+
+fn absolute(core: &mut ShellCore, dir: &str) -> String {
+    if dir.starts_with('/') {
+        return dir.to_string();
     }
 
-    let mut args = args.to_owned();
-    arg::consume_arg("--", &mut args);
+    let cwd = match core.get_current_directory() {
+        Some(p) => p.display().to_string(),
+        None => String::new(),
+    };
+    format!("{}/{}", cwd.trim_end_matches('/'), dir)
+}
+
+/* `.' and `..' are removed textually. The path before each `..' and
+ * the result must be existing directories. */
+fn canonical_path(path: &str) -> Option<String> {
+    let root = match path.starts_with("//") && !path.starts_with("///") {
+        true => "//",
+        false => "/",
+    };
+
+    let mut comps: Vec<&str> = vec![];
+    for c in path.split('/') {
+        match c {
+            "" | "." => {}
+            ".." => {
+                if !file_check::is_dir(&(root.to_owned() + &comps.join("/"))) {
+                    return None;
+                }
+                comps.pop();
+            }
+            _ => comps.push(c),
+        }
+    }
+
+    let ans = root.to_owned() + &comps.join("/");
+    match file_check::is_dir(&ans) {
+        true => Some(ans),
+        false => None,
+    }
+}
+
+/* the directory is reread with getcwd(3) */
+fn reset_pwd(core: &mut ShellCore) -> Option<String> {
+    let path = env::current_dir().ok()?;
+    core.set_current_directory(&path).ok()?;
+    Some(path.display().to_string())
+}
+
+/* Returns the new PWD, or None when the directory is changed
+ * but its name can't be obtained. */
+fn change_to_directory(
+    core: &mut ShellCore,
+    dir: &str,
+    physical: bool,
+) -> Result<Option<String>, io::Error> {
+    if physical {
+        env::set_current_dir(dir)?;
+        return Ok(reset_pwd(core));
+    }
+
+    let path = absolute(core, dir);
+    let canonical = canonical_path(&path);
+    if canonical.is_none() && core.options.query("posix") {
+        return Err(io::Error::from_raw_os_error(libc::ENOENT));
+    }
+
+    let err = match canonical {
+        Some(c) => match core.set_current_directory(&PathBuf::from(&c)) {
+            Ok(()) => return Ok(Some(c)),
+            Err(e) => e,
+        },
+        None => match env::set_current_dir(&path) {
+            Ok(()) => return Ok(reset_pwd(core)),
+            Err(e) => e,
+        },
+    };
+
+    /* the name given by the user is tried at last */
+    match !core.options.query("posix") && env::set_current_dir(dir).is_ok() {
+        true => Ok(reset_pwd(core)),
+        false => Err(err),
+    }
+}
+
+fn bind_pwd(core: &mut ShellCore, pwd: Option<String>, eflag: bool) -> i32 {
+    let old = match core.db.get_param("PWD") {
+        Ok(p) if !p.is_empty() => p,
+        _ => current_dir(core),
+    };
+    let _ = core.db.set_param("OLDPWD", &old, Some(0));
+
+    let pwd = match pwd {
+        Some(p) => p,
+        None => {
+            let msg =
+                "error retrieving current directory: getcwd: cannot access parent directories";
+            error_(1, "cd", msg, core);
+            return if eflag { 1 } else { 0 };
+        }
+    };
+
+    let _ = core.db.set_param("PWD", &pwd, Some(0));
+    super::dirs::sync_dirstack(core);
+    0
+}
 
-    if args.len() > 2 {
-        eprintln!("sush: cd: too many arguments");
-        return 1;
+fn current_dir(core: &mut ShellCore) -> String {
+    match core.get_current_directory() {
+        Some(p) => p.display().to_string(),
+        None => String::new(),
     }
+}
 
-    // only "cd"
-    if args.len() == 1 {
-        set_oldpwd(core);
-        let home = core.db.get_param("HOME").unwrap_or_default();
-        return change_directory(core, &home);
+/* CDPATH is not searched for names beginning with `/', `./' or `../' */
+fn search_cdpath(core: &mut ShellCore, dir: &str, physical: bool) -> Option<i32> {
+    if dir.starts_with('/')
+        || [".", ".."].contains(&dir)
+        || dir.starts_with("./")
+        || dir.starts_with("../")
+    {
+        return None;
     }
 
-    // cd -
-    if args[1] == "-" {
-        return cd_oldpwd(core);
+    let cdpath = core.db.get_param("CDPATH").ok()?;
+    if cdpath.is_empty() {
+        return None;
     }
 
-    // cd /some/dir
-    set_oldpwd(core);
-    change_directory(core, &args[1])
+    for entry in cdpath.split(':') {
+        let path = match entry.is_empty() {
+            true => format!("./{dir}"),
+            false => format!("{}/{dir}", entry.trim_end_matches('/')),
+        };
+
+        if let Ok(pwd) = change_to_directory(core, &path, physical) {
+            /* POSIX requires printing the directory found with a nonempty entry */
+            if !entry.is_empty() {
+                match physical {
+                    true => println!("{path}"),
+                    false => println!("{}", pwd.clone().unwrap_or_default()),
+                }
+            }
+            return Some(bind_pwd(core, pwd, false));
+        }
+    }
+    None
 }
 
-fn cd_oldpwd(core: &mut ShellCore) -> i32 {
-    match core.db.get_param("OLDPWD") {
-        Ok(old) => {
-            println!("{}", &old);
-            set_oldpwd(core);
-            change_directory(core, &old)
+fn cd_to(core: &mut ShellCore, dir: &str, physical: bool, eflag: bool, print: bool) -> i32 {
+    if let Some(es) = search_cdpath(core, dir, physical) {
+        return es;
+    }
+
+    let err = match change_to_directory(core, dir, physical) {
+        Ok(pwd) => {
+            if print {
+                println!("{dir}");
+            }
+            return bind_pwd(core, pwd, eflag);
         }
-        Err(_) => {
-            error::print("cd: OLDPWD not set", core);
-            1
+        Err(e) => e,
+    };
+
+    /* names given by cdable_vars and cdspell */
+    let mut alternatives = vec![];
+    if core.shopts.query("cdable_vars") {
+        alternatives.push(core.db.get_param(dir).unwrap_or_default());
+    }
+    if core.shopts.query("cdspell") && core.db.flags.contains('i') {
+        alternatives.extend(directory::correct_spelling(dir));
+    }
+
+    for alt in alternatives.into_iter().filter(|a| !a.is_empty()) {
+        if let Ok(pwd) = change_to_directory(core, &alt, physical) {
+            println!("{alt}");
+            return bind_pwd(core, pwd, eflag);
         }
     }
+
+    let msg = format!("{dir}: {}", errno_msg(&err));
+    error_(1, "cd", &msg, core)
 }
 
-fn set_oldpwd(core: &mut ShellCore) {
-    if let Some(old) = core.get_current_directory() {
-        let _ = core
-            .db
-            .set_param("OLDPWD", &old.display().to_string(), Some(0));
+pub fn cd(core: &mut ShellCore, args: &[String]) -> i32 {
+    if core.db.flags.contains('r') {
+        return error_(1, &args[0], "restricted", core);
+    }
+    super::dirs::load_dirstack(core);
+
+    let mut physical = core.options.query("physical");
+    let mut eflag = false;
+    let mut i = 1;
+
+    while i < args.len() && args[i].starts_with('-') && args[i] != "-" {
+        if args[i] == "--" {
+            i += 1;
+            break;
+        }
+
+        for c in args[i][1..].chars() {
+            match c {
+                'L' => physical = false,
+                'P' => physical = true,
+                'e' => eflag = true,
+                _ => {
+                    error_(2, &args[0], &format!("-{c}: invalid option"), core);
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    if args.len() > i + 1 {
+        return error_(1, &args[0], "too many arguments", core);
+    }
+
+    let (dir, print) = match args.get(i).map(|s| s.as_str()) {
+        None if !core.db.exist("HOME") => return error_(1, &args[0], "HOME not set", core),
+        None => (core.db.get_param("HOME").unwrap_or_default(), false),
+        Some("-") if !core.db.exist("OLDPWD") => {
+            return error_(1, &args[0], "OLDPWD not set", core);
+        }
+        Some("-") => (core.db.get_param("OLDPWD").unwrap_or_default(), true),
+        Some(dir) => (dir.to_string(), false),
     };
+
+    if dir.is_empty() {
+        return 0;
+    }
+
+    cd_to(core, &dir, physical, eflag && physical, print)
+}
+
+pub fn set_oldpwd(core: &mut ShellCore) {
+    let old = current_dir(core);
+    if !old.is_empty() {
+        let _ = core.db.set_param("OLDPWD", &old, Some(0));
+    }
 }
 
-fn change_directory(core: &mut ShellCore, target: &str) -> i32 {
-    let path = file::make_canonical_path(core, target);
-    if core.set_current_directory(&path).is_ok() {
-        let _ = core
-            .db
-            .set_param("PWD", &path.display().to_string(), Some(0));
-        0
-    } else {
-        eprintln!("sush: cd: {:?}: No such file or directory", &path);
-        1
+pub fn change_directory(core: &mut ShellCore, target: &str, com: &str) -> i32 {
+    let physical = core.options.query("physical");
+    match change_to_directory(core, target, physical) {
+        Ok(pwd) => {
+            let _ = core.db.set_param("PWD", &pwd.unwrap_or_default(), Some(0));
+            super::dirs::sync_dirstack(core);
+            0
+        }
+        Err(e) => {
+            let msg = format!("{target}: {}", errno_msg(&e));
+            error_(1, com, &msg, core)
+        }
     }
 }
@@ -1,10 +1,13 @@
 //SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
 //SPDX-License-Identifier: BSD-3-Clause
 
+use crate::elements::command::simple::hash as cmd_hash;
 use crate::elements::command::simple::SimpleCommand;
 use crate::elements::io::pipe::Pipe;
-use crate::utils::{arg, file};
-use crate::{error, file_check, proc_ctrl, utils, ShellCore};
+use crate::error::exec::ExecError;
+use super::type_::{Describe, Form};
+use crate::utils::file;
+use crate::{proc_ctrl, ShellCore};
 
 pub fn builtin(core: &mut ShellCore, args: &[String]) -> i32 {
     if args.len() <= 1 {
@@ -19,110 +22,104 @@ pub fn builtin(core: &mut ShellCore, args: &[String]) -> i32 {
     core.builtins[&args[1]](core, &args[1..])
 }
 
-fn command_v(words: &[String], core: &mut ShellCore, large_v: bool) -> i32 {
-    if words.is_empty() {
-        return 0;
+const USAGE: &str = "command: usage: command [-pVv] command [arg ...]";
+
+fn run(core: &mut ShellCore, words: &[String], path: &str) -> i32 {
+    if core.builtins.contains_key(&words[0]) {
+        return core.builtins[&words[0]](core, words);
     }
 
-    let mut return_value = 1;
+    let mut command = SimpleCommand::default();
+    let mut pipe = Pipe::new("".to_string());
+    command.args = words.to_vec();
+    command.command_path = path.to_string();
+    command.skip_functions = true;
+    if let Ok(pid) = command.exec_command(core, &mut pipe) {
+        proc_ctrl::wait_pipeline(core, vec![pid], false, false);
+    }
 
-    for com in words.iter() {
-        if utils::reserved(com) {
-            return_value = 0;
-            match large_v {
-                true => println!("{} is a shell keyword", &com),
-                false => println!("{}", &com),
-            }
-        } else if core.db.has_array_value("BASH_ALIASES", com) {
-            return_value = 0;
-            let alias = core.db.get_elem("BASH_ALIASES", com).unwrap();
-            match large_v {
-                true => println!("{} is aliased to `{}'", &com, &alias),
-                false => println!("alias {}='{}'", &com, &alias),
-            }
-        } else if core.builtins.contains_key(com) {
-            return_value = 0;
+    core.db.exit_status
+}
 
-            match large_v {
-                true => println!("{} is a shell builtin", &com),
-                false => println!("{}", &com),
-            }
-        } else if core.db.functions.contains_key(com) {
-            return_value = 0;
-            match large_v {
-                true => {
-                    println!("{} is a function", &com);
-                    core.db.functions.get_mut(com).unwrap().pretty_print(0);
-                }
-                false => println!("{}", &com),
-            }
-        } else if let Some(path) = file::search_command(com) {
-            return_value = 0;
-            match large_v {
-                true => println!("{} is {}", &com, &path),
-                false => println!("{}", &com),
-            }
-        } else if file_check::is_executable(com) {
-            return_value = 0;
-            match large_v {
-                true => println!("{} is {}", &com, &com),
-                false => println!("{}", &com),
-            }
-        } else if large_v {
-            let msg = format!("command: {com}: not found");
-            error::print(&msg, core);
-        }
+/* the command is searched in the standard PATH without changing $PATH */
+fn run_with_standard_path(core: &mut ShellCore, words: &[String]) -> i32 {
+    if core.builtins.contains_key(&words[0]) || words[0].contains('/') {
+        return run(core, words, "");
     }
 
-    return_value
+    match file::search_commands(&words[0], &file::standard_path()).first() {
+        Some(path) => {
+            let _ = cmd_hash::regist(&words[0], path, core);
+            cmd_hash::count_up(&words[0], core);
+            run(core, words, path)
+        }
+        None => {
+            ExecError::CommandNotFound(words[0].clone()).print(core);
+            127
+        }
+    }
 }
 
 pub fn command(core: &mut ShellCore, args: &[String]) -> i32 {
-    if args.len() > 1 {
-        if core.subst_builtins.contains_key(&args[1]) {
-            //TODO
-            return super::error_(1, &args[0], "substitution command are not supported", core);
-        }
+    if args.len() > 1 && core.subst_builtins.contains_key(&args[1]) {
+        //TODO
+        return super::error_(1, &args[0], "substitution command are not supported", core);
     }
 
-    let mut args = arg::dissolve_options(args);
-    if core.db.flags.contains('r') && arg::consume_arg("-p", &mut args) {
-        return super::error_(1, &args[0], "-p: restricted", core);
-    }
+    let mut form = None;
+    let mut std_path = false;
+    let mut i = 1;
 
-    if args.len() <= 1 {
-        return 0;
-    }
+    while i < args.len() && args[i].starts_with('-') && args[i] != "-" {
+        if args[i] == "--" {
+            i += 1;
+            break;
+        }
 
-    let mut pos = 1;
-    while args.len() > pos {
-        match args[pos].starts_with("-") {
-            true => pos += 1,
-            false => break,
+        for c in args[i][1..].chars() {
+            match c {
+                'p' => std_path = true,
+                'v' => form = Some(Form::Reusable),
+                'V' => form = Some(Form::Long),
+                _ => {
+                    super::error_(2, &args[0], &format!("-{c}: invalid option"), core);
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            }
         }
+        i += 1;
     }
 
-    let words = args[pos..].to_vec();
-    if words.is_empty() {
-        return 0;
+    if std_path && core.db.flags.contains('r') {
+        return super::error_(1, &args[0], "-p: restricted", core);
     }
 
-    let mut args = args[..pos].to_vec();
-    args = arg::dissolve_options(&args);
-
-    let last_option = args.last().unwrap();
-    if last_option == "-V" || last_option == "-v" {
-        return command_v(&words, core, last_option == "-V");
-    } else if core.builtins.contains_key(&words[0]) {
-        return core.builtins[&words[0]](core, &words[..]);
+    let words = &args[i..];
+    if words.is_empty() {
+        return 0;
     }
 
-    let mut command = SimpleCommand::default();
-    let mut pipe = Pipe::new("".to_string());
-    command.args = words;
-    if let Ok(pid) = command.exec_command(core, &mut pipe) {
-        proc_ctrl::wait_pipeline(core, vec![pid], false, false);
+    let form = match form {
+        Some(f) => f,
+        None if std_path => return run_with_standard_path(core, words),
+        None => return run(core, words, ""),
+    };
+
+    let desc = Describe {
+        form,
+        std_path,
+        abs_path: form == Form::Long,
+        ..Default::default()
+    };
+
+    let mut exit_status = 1;
+    for com in words {
+        if desc.describe(core, com) {
+            exit_status = 0;
+        } else if form == Form::Long {
+            super::error_(1, &args[0], &format!("{com}: not found"), core);
+        }
     }
-
-    core.db.exit_status
+    exit_status
 }
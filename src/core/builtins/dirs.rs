@@ -0,0 +1,290 @@
+//SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
+//SPDX-License-Identifier: BSD-3-Clause
+
+use super::cd;
+use super::error_;
+use crate::ShellCore;
+
+const DIRS_USAGE: &str = "dirs: usage: dirs [-clpv] [+N] [-N]";
+const PUSHD_USAGE: &str = "pushd: usage: pushd [-n] [+N | -N | dir]";
+const POPD_USAGE: &str = "popd: usage: popd [-n] [+N | -N]";
+
+fn usage_error(core: &mut ShellCore, com: &str, msg: &str, usage: &str) -> i32 {
+    error_(2, com, msg, core);
+    eprintln!("{usage}");
+    2
+}
+
+fn current_dir(core: &mut ShellCore) -> String {
+    match core.get_current_directory() {
+        Some(path) => path.display().to_string(),
+        None => core.db.get_param("PWD").unwrap_or_default(),
+    }
+}
+
+/* the current directory followed by the saved ones */
+fn full_stack(core: &mut ShellCore) -> Vec<String> {
+    let mut ans = vec![current_dir(core)];
+    ans.extend(core.dir_stack.clone());
+    ans
+}
+
+/* takes assignments to DIRSTACK[1], DIRSTACK[2], ... back to the stack */
+pub fn load_dirstack(core: &mut ShellCore) {
+    for i in 0..core.dir_stack.len() {
+        let dir = core.db.get_elem("DIRSTACK", &(i + 1).to_string());
+        let dir = dir.unwrap_or_default();
+        if !dir.is_empty() {
+            core.dir_stack[i] = dir;
+        }
+    }
+}
+
+pub fn sync_dirstack(core: &mut ShellCore) {
+    let stack = full_stack(core);
+    let _ = core.db.init_array("DIRSTACK", Some(stack), Some(0), false);
+}
+
+fn is_index(arg: &str) -> bool {
+    arg.len() > 1 && (arg.starts_with('+') || arg.starts_with('-'))
+}
+
+/* +N counts from the left of the list shown by dirs, -N from the right */
+fn stack_index(arg: &str, len: usize) -> Result<usize, String> {
+    let n = match arg[1..].chars().all(|c| c.is_ascii_digit()) {
+        true => arg[1..].parse::<usize>().ok(),
+        false => None,
+    };
+
+    match n {
+        None => Err(format!("{arg}: invalid number")),
+        Some(n) if n >= len => Err(format!("{arg}: directory stack index out of range")),
+        Some(n) if arg.starts_with('+') => Ok(n),
+        Some(n) => Ok(len - 1 - n),
+    }
+}
+
+/* used for the tilde expansion of ~N, ~+N and ~-N */
+pub fn get_entry(core: &mut ShellCore, spec: &str) -> Option<String> {
+    let spec = match spec.starts_with(['+', '-']) {
+        true => spec.to_string(),
+        false => format!("+{spec}"),
+    };
+
+    load_dirstack(core);
+    let stack = full_stack(core);
+    match is_index(&spec) {
+        true => stack_index(&spec, stack.len()).ok().map(|i| stack[i].clone()),
+        false => None,
+    }
+}
+
+fn tilde_path(core: &mut ShellCore, path: &str) -> String {
+    let home = core.db.get_param("HOME").unwrap_or_default();
+    if home.is_empty() {
+        return path.to_string();
+    }
+
+    if path == home {
+        return "~".to_string();
+    }
+    match path.strip_prefix(&(home.trim_end_matches('/').to_string() + "/")) {
+        Some(rest) => format!("~/{rest}"),
+        None => path.to_string(),
+    }
+}
+
+fn print_stack(core: &mut ShellCore, flags: &str, only: Option<usize>) {
+    let long = flags.contains('l');
+    let verbose = flags.contains('v');
+
+    let mut entries = vec![];
+    for (i, dir) in full_stack(core).into_iter().enumerate() {
+        if only.is_some_and(|n| n != i) {
+            continue;
+        }
+
+        let dir = match long {
+            true => dir,
+            false => tilde_path(core, &dir),
+        };
+        match verbose {
+            true => entries.push(format!("{i:2}  {dir}")),
+            false => entries.push(dir),
+        }
+    }
+
+    match flags.contains('p') || verbose {
+        true => entries.iter().for_each(|e| println!("{e}")),
+        false => println!("{}", entries.join(" ")),
+    }
+}
+
+pub fn dirs(core: &mut ShellCore, args: &[String]) -> i32 {
+    load_dirstack(core);
+    let mut flags = String::new();
+    let mut index = None;
+
+    for arg in &args[1..] {
+        if arg.starts_with('-') && arg[1..].chars().all(|c| "clpv".contains(c)) {
+            flags += &arg[1..];
+        } else if is_index(arg) {
+            index = Some(arg.clone());
+        } else {
+            let msg = format!("{arg}: invalid option");
+            return usage_error(core, &args[0], &msg, DIRS_USAGE);
+        }
+    }
+
+    if flags.contains('c') {
+        core.dir_stack.clear();
+        sync_dirstack(core);
+        return 0;
+    }
+
+    let only = match index {
+        Some(arg) => match stack_index(&arg, core.dir_stack.len() + 1) {
+            Ok(i) => Some(i),
+            Err(msg) if msg.ends_with("invalid number") => {
+                return usage_error(core, &args[0], &msg, DIRS_USAGE);
+            }
+            Err(msg) => {
+                let msg = msg.trim_start_matches(['+', '-']).to_string();
+                return error_(1, &args[0], &msg, core);
+            }
+        },
+        None => None,
+    };
+
+    print_stack(core, &flags, only);
+    0
+}
+
+fn pushd_rotate(core: &mut ShellCore, arg: &str, no_cd: bool) -> i32 {
+    let stack = full_stack(core);
+    let pos = match stack_index(arg, stack.len()) {
+        Ok(pos) => pos,
+        Err(msg) => return error_(1, "pushd", &msg, core),
+    };
+
+    let rotated = [&stack[pos..], &stack[..pos]].concat();
+    if !no_cd && pos != 0 {
+        cd::set_oldpwd(core);
+        if cd::change_directory(core, &rotated[0], "pushd") != 0 {
+            return 1;
+        }
+    }
+
+    core.dir_stack = rotated[1..].to_vec();
+    sync_dirstack(core);
+    0
+}
+
+pub fn pushd(core: &mut ShellCore, args: &[String]) -> i32 {
+    load_dirstack(core);
+    let mut args = args[1..].to_vec();
+    let no_cd = args.first().is_some_and(|a| a == "-n");
+    if no_cd {
+        args.remove(0);
+    }
+    if args.first().is_some_and(|a| a == "--") {
+        args.remove(0);
+    } else if args.first().is_some_and(|a| is_index(a)) {
+        match stack_index(&args[0], 1) {
+            Err(msg) if msg.ends_with("invalid number") => {
+                return usage_error(core, "pushd", &msg, PUSHD_USAGE);
+            }
+            _ if core.dir_stack.is_empty() => {
+                return error_(1, "pushd", "directory stack empty", core);
+            }
+            _ => {}
+        }
+    }
+
+    if args.len() > 1 {
+        return error_(1, "pushd", "too many arguments", core);
+    }
+
+    let exit_status = match args.first() {
+        None if no_cd => return 0,
+        None if core.dir_stack.is_empty() => {
+            return error_(1, "pushd", "no other directory", core);
+        }
+        None => pushd_rotate(core, "+1", false),
+        Some(arg) if is_index(arg) && no_cd => return pushd_rotate(core, arg, true),
+        Some(arg) if is_index(arg) => pushd_rotate(core, arg, false),
+        Some(dir) if no_cd => {
+            core.dir_stack.insert(0, dir.clone());
+            sync_dirstack(core);
+            0
+        }
+        Some(dir) => {
+            let dir = match dir.as_str() {
+                "-" => core.db.get_param("OLDPWD").unwrap_or_default(),
+                _ => dir.clone(),
+            };
+            let old = current_dir(core);
+            cd::set_oldpwd(core);
+            match cd::change_directory(core, &dir, "pushd") {
+                0 => {
+                    core.dir_stack.insert(0, old);
+                    sync_dirstack(core);
+                    0
+                }
+                _ => 1,
+            }
+        }
+    };
+
+    if exit_status == 0 {
+        print_stack(core, "", None);
+    }
+    exit_status
+}
+
+pub fn popd(core: &mut ShellCore, args: &[String]) -> i32 {
+    load_dirstack(core);
+    let mut no_cd = false;
+    let mut index = "+0".to_string();
+
+    for arg in &args[1..] {
+        if arg == "-n" {
+            no_cd = true;
+        } else if is_index(arg) {
+            index = arg.clone();
+        } else if arg != "--" {
+            let msg = format!("{arg}: invalid argument");
+            return usage_error(core, &args[0], &msg, POPD_USAGE);
+        }
+    }
+
+    let stack = full_stack(core);
+    let mut pos = match stack_index(&index, stack.len()) {
+        Err(msg) if msg.ends_with("invalid number") => {
+            return usage_error(core, &args[0], &msg, POPD_USAGE);
+        }
+        _ if core.dir_stack.is_empty() => {
+            return error_(1, &args[0], "directory stack empty", core);
+        }
+        Ok(pos) => pos,
+        Err(msg) => return error_(1, &args[0], &msg, core),
+    };
+
+    if pos == 0 && no_cd {
+        pos = 1;
+    }
+
+    if pos == 0 {
+        cd::set_oldpwd(core);
+        if cd::change_directory(core, &stack[1], "popd") != 0 {
+            return 1;
+        }
+        core.dir_stack.remove(0);
+    } else {
+        core.dir_stack.remove(pos - 1);
+    }
+
+    sync_dirstack(core);
+    print_stack(core, "", None);
+    0
+}
@@ -0,0 +1,255 @@
+//SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
+//SPDX-License-Identifier: BSD-3-Clause
+
+use super::error_;
+use crate::ShellCore;
+use std::ffi::{c_char, c_int, c_uint, c_void, CStr, CString};
+use std::io::Write;
+
+const USAGE: &str = "enable: usage: enable [-a] [-dnps] [-f filename] [name ...]";
+
+const SPECIAL_BUILTINS: [&str; 16] = [
+    ".", ":", "break", "continue", "eval", "exec", "exit", "export", "readonly", "return", "set",
+    "shift", "source", "times", "trap", "unset",
+];
+
+/* The ABI of loadable builtins. See docs/LOADABLE_BUILTINS.md.
+ * Bump the version whenever SushBuiltin changes. */
+pub const LOADABLE_ABI_VERSION: c_uint = 1;
+
+type LoadableFn = extern "C" fn(c_int, *const *const c_char) -> c_int;
+
+#[repr(C)]
+struct SushBuiltin {
+    abi_version: c_uint,
+    name: *const c_char,
+    function: Option<LoadableFn>,
+    short_doc: *const c_char,
+}
+
+pub struct Loadable {
+    handle: *mut c_void,
+    function: LoadableFn,
+    pub short_doc: String,
+}
+
+fn dl_error() -> String {
+    let err = unsafe { libc::dlerror() };
+    match err.is_null() {
+        true => "unknown error".to_string(),
+        false => unsafe { CStr::from_ptr(err) }.to_string_lossy().to_string(),
+    }
+}
+
+fn load(file: &str, name: &str) -> Result<Loadable, String> {
+    let c_file = CString::new(file).map_err(|e| e.to_string())?;
+    let handle = unsafe { libc::dlopen(c_file.as_ptr(), libc::RTLD_LAZY) };
+    if handle.is_null() {
+        return Err(format!("cannot open shared object {file}: {}", dl_error()));
+    }
+
+    let symbol = format!("{name}_struct");
+    let c_symbol = CString::new(symbol.clone()).map_err(|e| e.to_string())?;
+    let ptr = unsafe { libc::dlsym(handle, c_symbol.as_ptr()) } as *const SushBuiltin;
+    if ptr.is_null() {
+        let msg = format!("cannot find {symbol} in shared object {file}: {}", dl_error());
+        unsafe { libc::dlclose(handle) };
+        return Err(msg);
+    }
+
+    let entry = unsafe { &*ptr };
+    let function = match (entry.abi_version, entry.function) {
+        (LOADABLE_ABI_VERSION, Some(f)) => f,
+        (LOADABLE_ABI_VERSION, None) => {
+            unsafe { libc::dlclose(handle) };
+            return Err(format!("{name}: no function in {symbol}"));
+        }
+        (v, _) => {
+            unsafe { libc::dlclose(handle) };
+            return Err(format!(
+                "{name}: loadable ABI version {v} is not supported (expected {LOADABLE_ABI_VERSION})"
+            ));
+        }
+    };
+
+    let short_doc = match entry.short_doc.is_null() {
+        true => String::new(),
+        false => unsafe { CStr::from_ptr(entry.short_doc) }.to_string_lossy().to_string(),
+    };
+
+    Ok(Loadable {
+        handle,
+        function,
+        short_doc,
+    })
+}
+
+/* every loadable builtin is registered with this function */
+fn run_loadable(core: &mut ShellCore, args: &[String]) -> i32 {
+    let function = match core.loadables.get(&args[0]) {
+        Some(l) => l.function,
+        None => return 127,
+    };
+
+    let c_args: Vec<CString> = args
+        .iter()
+        .map(|a| CString::new(a.replace('\0', "")).unwrap())
+        .collect();
+    let mut argv: Vec<*const c_char> = c_args.iter().map(|a| a.as_ptr()).collect();
+    argv.push(std::ptr::null());
+
+    let _ = std::io::stdout().flush();
+    let exit_status = function(c_args.len() as c_int, argv.as_ptr());
+    unsafe { libc::fflush(std::ptr::null_mut()) };
+    exit_status
+}
+
+fn unload(core: &mut ShellCore, name: &str) -> i32 {
+    let loadable = match core.loadables.remove(name) {
+        Some(l) => l,
+        None => {
+            let msg = format!("{name}: not dynamically loaded");
+            return error_(1, "enable", &msg, core);
+        }
+    };
+
+    core.builtins.remove(name);
+    core.disabled_builtins.remove(name);
+    unsafe { libc::dlclose(loadable.handle) };
+    0
+}
+
+fn load_builtins(core: &mut ShellCore, file: &str, names: &[String]) -> i32 {
+    let mut exit_status = 0;
+    for name in names {
+        match load(file, name) {
+            Ok(loadable) => {
+                if let Some(old) = core.loadables.insert(name.clone(), loadable) {
+                    unsafe { libc::dlclose(old.handle) };
+                }
+                core.disabled_builtins.remove(name);
+                core.builtins.insert(name.clone(), run_loadable);
+            }
+            Err(msg) => exit_status = error_(1, "enable", &msg, core),
+        }
+    }
+    exit_status
+}
+
+fn switch(core: &mut ShellCore, name: &str, enable: bool) -> i32 {
+    let ok = match enable {
+        true => {
+            if let Some(f) = core.disabled_builtins.remove(name) {
+                core.builtins.insert(name.to_string(), f);
+            }
+            if let Some(f) = core.disabled_subst_builtins.remove(name) {
+                core.subst_builtins.insert(name.to_string(), f);
+            }
+            core.builtins.contains_key(name) || core.subst_builtins.contains_key(name)
+        }
+        false => {
+            if let Some(f) = core.builtins.remove(name) {
+                core.disabled_builtins.insert(name.to_string(), f);
+            }
+            if let Some(f) = core.subst_builtins.remove(name) {
+                core.disabled_subst_builtins.insert(name.to_string(), f);
+            }
+            core.disabled_builtins.contains_key(name)
+                || core.disabled_subst_builtins.contains_key(name)
+        }
+    };
+
+    match ok {
+        true => 0,
+        false => {
+            let msg = format!("{name}: not a shell builtin");
+            error_(1, "enable", &msg, core)
+        }
+    }
+}
+
+fn print_list(core: &mut ShellCore, flags: &str) {
+    let mut list: Vec<(String, bool)> = vec![];
+    if !flags.contains('n') || flags.contains('a') {
+        list.extend(core.builtins.keys().map(|k| (k.clone(), true)));
+        list.extend(core.subst_builtins.keys().map(|k| (k.clone(), true)));
+    }
+    if flags.contains('n') || flags.contains('a') {
+        list.extend(core.disabled_builtins.keys().map(|k| (k.clone(), false)));
+        list.extend(core.disabled_subst_builtins.keys().map(|k| (k.clone(), false)));
+    }
+
+    list.sort();
+    list.dedup();
+    for (name, enabled) in list {
+        if flags.contains('s') && !SPECIAL_BUILTINS.contains(&name.as_str()) {
+            continue;
+        }
+        match enabled {
+            true => println!("enable {name}"),
+            false => println!("enable -n {name}"),
+        }
+    }
+}
+
+pub fn enable(core: &mut ShellCore, args: &[String]) -> i32 {
+    let mut flags = String::new();
+    let mut file = None;
+    let mut i = 1;
+
+    while i < args.len() && args[i].starts_with('-') && args[i] != "-" {
+        if args[i] == "--" {
+            i += 1;
+            break;
+        }
+
+        for (pos, c) in args[i].char_indices().skip(1) {
+            match c {
+                'a' | 'd' | 'n' | 'p' | 's' => flags.push(c),
+                'f' => {
+                    file = match &args[i][pos + 1..] {
+                        "" if i + 1 < args.len() => {
+                            i += 1;
+                            Some(args[i].clone())
+                        }
+                        "" => {
+                            error_(2, "enable", "-f: option requires an argument", core);
+                            eprintln!("{USAGE}");
+                            return 2;
+                        }
+                        rest => Some(rest.to_string()),
+                    };
+                    break;
+                }
+                _ => {
+                    error_(2, "enable", &format!("-{c}: invalid option"), core);
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let names = &args[i..];
+    if let Some(file) = file {
+        return load_builtins(core, &file, names);
+    }
+
+    if names.is_empty() {
+        print_list(core, &flags);
+        return 0;
+    }
+
+    let mut exit_status = 0;
+    for name in names {
+        let es = match flags.contains('d') {
+            true => unload(core, name),
+            false => switch(core, name, !flags.contains('n')),
+        };
+        if es != 0 {
+            exit_status = es;
+        }
+    }
+    exit_status
+}
@@ -1,50 +1,145 @@
 //SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
 //SPDX-License-Identifier: BSD-3-Clause
 
-use crate::{proc_ctrl, ShellCore};
+use super::error_;
+use crate::elements::command::simple::hash as cmd_hash;
+use crate::utils::{c_string, exit};
+use crate::ShellCore;
 use nix::errno::Errno;
 use nix::unistd;
 use std::ffi::CString;
 
-pub fn exec(core: &mut ShellCore, args: &[String]) -> i32 {
-    let args = args.to_owned();
-    if core.db.flags.contains('r') {
-        return super::error_(1, &args[0], "restricted", core);
+const USAGE: &str = "exec: usage: exec [-cl] [-a name] [command [argument ...]] [redirection ...]";
+
+fn command_path(core: &mut ShellCore, name: &str) -> String {
+    if name.contains('/') {
+        return name.to_string();
     }
 
-    if args.len() == 1 {
-        return 0;
+    let hashed = core.db.get_elem("BASH_CMDS", name).unwrap_or_default();
+    if !hashed.is_empty() {
+        return hashed;
     }
+    cmd_hash::resolve_path(name, core).unwrap_or_default()
+}
+
+/* returns only when the command can't be executed */
+fn execute(path: &str, argv: &[String], clear_env: bool) -> Errno {
+    let cpath = CString::new(path).unwrap();
+    let mut cargs = c_string::to_cargs(argv);
+    let env: &[CString] = &[];
 
-    if core.db.flags.contains('i') || core.shopts.query("execfail") {
-        exec_command(&args[1..], core, "")
-    } else {
-        proc_ctrl::exec_command(&args[1..], core, "")
+    let err = match clear_env {
+        true => unistd::execve(&cpath, &cargs, env).unwrap_err(),
+        false => unistd::execv(&cpath, &cargs).unwrap_err(),
+    };
+    if err != Errno::ENOEXEC {
+        return err;
+    }
+
+    /* a file without a shebang is given to sh like execvp(3) */
+    cargs.insert(1, cpath);
+    let sh = CString::new("/bin/sh").unwrap();
+    match clear_env {
+        true => unistd::execve(&sh, &cargs, env).unwrap_err(),
+        false => unistd::execv(&sh, &cargs).unwrap_err(),
     }
 }
 
-fn exec_command(args: &[String], core: &mut ShellCore, fullpath: &str) -> i32 {
-    let cargs: Vec<CString> = args
-        .iter()
-        .map(|a| CString::new(a.to_string()).unwrap())
-        .collect();
-    let cfullpath = CString::new(fullpath).unwrap();
+fn exec_command(
+    core: &mut ShellCore,
+    args: &[String],
+    argv0: Option<String>,
+    clear_env: bool,
+) -> i32 {
+    let mut argv = args.to_vec();
+    if let Some(name) = argv0 {
+        argv[0] = name;
+    }
+
+    let path = command_path(core, &args[0]);
+    if path.is_empty() {
+        return error_(127, "exec", &format!("{}: not found", &args[0]), core);
+    }
+
+    match execute(&path, &argv, clear_env) {
+        Errno::E2BIG => error_(
+            126,
+            "exec",
+            &format!("{path}: Argument list too long"),
+            core,
+        ),
+        Errno::ENOENT => error_(127, &path, "No such file or directory", core),
+        e => {
+            error_(1, &path, e.desc(), core);
+            error_(
+                126,
+                "exec",
+                &format!("{path}: cannot execute: {}", e.desc()),
+                core,
+            )
+        }
+    }
+}
 
-    if !fullpath.is_empty() {
-        let _ = unistd::execv(&cfullpath, &cargs);
+pub fn exec(core: &mut ShellCore, args: &[String]) -> i32 {
+    if core.db.flags.contains('r') {
+        return error_(1, &args[0], "restricted", core);
     }
-    let result = unistd::execvp(&cargs[0], &cargs);
 
-    match result {
-        Err(Errno::E2BIG) => super::error_(126, &args[0], "Arg list too long", core),
-        Err(Errno::EACCES) => {
-            super::error_(126, &args[0], "cannot execute: Permission denied", core)
+    let mut argv0 = None;
+    let mut clear_env = false;
+    let mut login = false;
+    let mut i = 1;
+
+    while i < args.len() && args[i].starts_with('-') && args[i] != "-" {
+        if args[i] == "--" {
+            i += 1;
+            break;
         }
-        Err(Errno::ENOENT) => super::error_(127, &args[0], "No such file or directory", core),
-        Err(e) => {
-            let msg = format!("{:?}", &e);
-            super::error_(127, &args[0], &msg, core)
+
+        for (pos, c) in args[i].char_indices().skip(1) {
+            match c {
+                'c' => clear_env = true,
+                'l' => login = true,
+                'a' => {
+                    argv0 = match &args[i][pos + 1..] {
+                        "" if i + 1 < args.len() => {
+                            i += 1;
+                            Some(args[i].clone())
+                        }
+                        "" => {
+                            error_(2, &args[0], "-a: option requires an argument", core);
+                            eprintln!("{USAGE}");
+                            return 2;
+                        }
+                        rest => Some(rest.to_string()),
+                    };
+                    break;
+                }
+                _ => {
+                    error_(2, &args[0], &format!("-{c}: invalid option"), core);
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            }
         }
-        _ => 127,
+        i += 1;
+    }
+
+    if i == args.len() {
+        return 0;
+    }
+
+    if login {
+        let name = argv0.unwrap_or(args[i].clone());
+        argv0 = Some(format!("-{name}"));
+    }
+
+    let exit_status = exec_command(core, &args[i..], argv0, clear_env);
+    if !core.db.flags.contains('i') && !core.shopts.query("execfail") {
+        core.db.exit_status = exit_status;
+        exit::normal(core);
     }
+    exit_status
 }
@@ -1,49 +1,160 @@
 //SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
 //SPDX-License-Identifier: BSD-3-Clause
 
-use crate::utils::arg;
-use crate::ShellCore;
-
-fn print_all(core: &mut ShellCore) -> i32 {
-    println!("hits	command");
-
-    for com in core.db.get_indexes_all("BASH_CMDS") {
-        if let Ok(path) = core.db.get_elem("BASH_CMDS", &com) {
-            if let Some(n) = core.db.hash_counter.get(&com) {
-                println!("{:4}\t{}", &n, &path);
-            } else {
-                core.db.hash_counter.insert(com, 0);
-                println!("   0\t{}", &path);
-            }
+use super::error_;
+use crate::elements::command::simple::hash as cmd_hash;
+use crate::{file_check, ShellCore};
+
+const USAGE: &str = "hash: usage: hash [-lr] [-p pathname] [-dt] [name ...]";
+
+/* returns false if the table is empty */
+fn print_all(core: &mut ShellCore, portable: bool) -> bool {
+    let coms = core.db.get_indexes_all("BASH_CMDS");
+    if coms.is_empty() {
+        return false;
+    }
+
+    if !portable {
+        println!("hits\tcommand");
+    }
+
+    for com in coms {
+        let path = core.db.get_elem("BASH_CMDS", &com).unwrap_or_default();
+        let n = *core.db.hash_counter.entry(com.clone()).or_insert(0);
+        match portable {
+            true => println!("builtin hash -p {path} {com}"),
+            false => println!("{n:4}\t{path}"),
         }
     }
+    true
+}
+
+fn print_targets(core: &mut ShellCore, names: &[String], portable: bool) -> i32 {
+    let mut exit_status = 0;
+    for name in names {
+        let path = core.db.get_elem("BASH_CMDS", name).unwrap_or_default();
+        if path.is_empty() {
+            exit_status = error_(1, "hash", &format!("{name}: not found"), core);
+            continue;
+        }
 
+        cmd_hash::count_up(name, core);
+        match (portable, names.len() > 1) {
+            (true, _) => println!("builtin hash -p {path} {name}"),
+            (false, true) => println!("{name}\t{path}"),
+            (false, false) => println!("{path}"),
+        }
+    }
+    exit_status
+}
+
+fn remove(core: &mut ShellCore, name: &str) -> i32 {
+    let path = core.db.get_elem("BASH_CMDS", name).unwrap_or_default();
+    if path.is_empty() {
+        return error_(1, "hash", &format!("{name}: not found"), core);
+    }
+
+    let _ = core.db.unset_array_elem("BASH_CMDS", name);
+    core.db.hash_counter.remove(name);
     0
 }
 
+fn regist_path(core: &mut ShellCore, name: &str, path: &str) -> i32 {
+    if file_check::is_dir(path) {
+        return error_(1, "hash", &format!("{path}: Is a directory"), core);
+    }
+
+    if let Err(e) = core.db.set_assoc_elem("BASH_CMDS", name, path, Some(0)) {
+        return error_(1, "hash", &String::from(&e), core);
+    }
+    core.db.hash_counter.insert(name.to_string(), 0);
+    0
+}
+
+fn regist(core: &mut ShellCore, name: &str) -> i32 {
+    if core.db.functions.contains_key(name)
+        || core.builtins.contains_key(name)
+        || core.subst_builtins.contains_key(name)
+    {
+        return 0;
+    }
+
+    let _ = core.db.unset_array_elem("BASH_CMDS", name);
+    match cmd_hash::resolve_path(name, core) {
+        Ok(path) if !path.is_empty() => 0,
+        _ => error_(1, "hash", &format!("{name}: not found"), core),
+    }
+}
+
 pub fn hash(core: &mut ShellCore, args: &[String]) -> i32 {
-    let args = args.to_owned();
-    let mut args = arg::dissolve_options(&args);
+    let mut flags = String::new();
+    let mut pathname = None;
+    let mut i = 1;
+
+    while i < args.len() && args[i].starts_with('-') && args[i] != "-" {
+        if args[i] == "--" {
+            i += 1;
+            break;
+        }
 
-    if args.len() == 1 {
-        return print_all(core);
+        for (pos, c) in args[i].char_indices().skip(1) {
+            match c {
+                'd' | 'l' | 'r' | 't' => flags.push(c),
+                'p' => {
+                    pathname = match &args[i][pos + 1..] {
+                        "" if i + 1 < args.len() => {
+                            i += 1;
+                            Some(args[i].clone())
+                        }
+                        "" => {
+                            error_(2, "hash", "-p: option requires an argument", core);
+                            eprintln!("{USAGE}");
+                            return 2;
+                        }
+                        rest => Some(rest.to_string()),
+                    };
+                    break;
+                }
+                _ => {
+                    error_(2, "hash", &format!("-{c}: invalid option"), core);
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            }
+        }
+        i += 1;
     }
 
-    if arg::consume_arg("-p", &mut args) {
-        if args.len() == 1 {
-            return super::error_(1, "hash", "-p: option requires an argument", core);
+    let names = &args[i..];
+    let portable = flags.contains('l');
+    if names.is_empty() {
+        if flags.contains('d') || flags.contains('t') {
+            let opt = if flags.contains('d') { "-d" } else { "-t" };
+            return error_(1, "hash", &format!("{opt}: option requires an argument"), core);
         }
-        if args.len() == 2 {
-            return super::error_(1, "hash", "still not implemented", core);
+        if !flags.contains('r') && !print_all(core, portable) && !portable {
+            println!("hash: hash table empty");
         }
+    }
 
-        if let Err(e) = core
-            .db
-            .set_assoc_elem("BASH_CMDS", &args[2], &args[1], Some(0))
-        {
-            let msg = String::from(&e);
-            return super::error_(1, "hash", &msg, core);
+    if flags.contains('r') {
+        core.db.flush_hash();
+    }
+
+    if flags.contains('t') {
+        return print_targets(core, names, portable);
+    }
+
+    let mut exit_status = 0;
+    for name in names.iter().filter(|n| !n.contains('/')) {
+        let es = match (&pathname, flags.contains('d')) {
+            (Some(path), _) => regist_path(core, name, path),
+            (None, true) => remove(core, name),
+            (None, false) => regist(core, name),
+        };
+        if es != 0 {
+            exit_status = es;
         }
     }
-    0
+    exit_status
 }
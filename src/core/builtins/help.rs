@@ -0,0 +1,249 @@
+//SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
+//SPDX-License-Identifier: BSD-3-Clause
+
+use super::error_;
+use crate::utils::glob;
+use crate::{i18n, ShellCore};
+
+const USAGE: &str = "help: usage: help [-dms] [pattern ...]";
+
+/* reserved words are explained in the topic of the command they belong to */
+const KEYWORD_TOPICS: [(&str, &str); 17] = [
+    ("[[", "[[ ... ]]"),
+    ("]]", "[[ ... ]]"),
+    ("{", "{ ... }"),
+    ("}", "{ ... }"),
+    ("while", "while"),
+    ("for", "for"),
+    ("do", "do ... done"),
+    ("done", "do ... done"),
+    ("if", "if"),
+    ("then", "if"),
+    ("elif", "if"),
+    ("else", "if"),
+    ("fi", "if"),
+    ("case", "case"),
+    ("coproc", "coproc"),
+    ("esac", "case"),
+    ("repeat", "repeat"),
+];
+
+struct Entry {
+    name: String,
+    synopsis: String,
+    desc: Vec<String>,
+}
+
+impl Entry {
+    fn short(&self) -> &str {
+        self.desc.first().map(|s| s.as_str()).unwrap_or("")
+    }
+}
+
+/* Fluent identifiers can't contain symbols */
+fn message_id(topic: &str) -> String {
+    let id = match topic {
+        ":" => "colon",
+        "." => "dot",
+        "[" => "bracket",
+        "[[ ... ]]" => "double-bracket",
+        "{ ... }" => "brace",
+        "do ... done" => "do",
+        _ => topic,
+    };
+    format!("help-{id}")
+}
+
+fn entry(core: &ShellCore, topic: &str) -> Entry {
+    let id = message_id(topic);
+    let (synopsis, desc) = match core.loadables.get(topic) {
+        Some(l) => (topic.to_string(), l.short_doc.clone()),
+        None => (
+            i18n::fl_opt(&id).unwrap_or(topic.to_string()),
+            i18n::fl_attr(&id, "desc").unwrap_or_default(),
+        ),
+    };
+
+    Entry {
+        name: topic.to_string(),
+        synopsis,
+        desc: desc.lines().map(|s| s.to_string()).collect(),
+    }
+}
+
+/* topics with the flags telling if they are enabled */
+fn topics(core: &ShellCore) -> Vec<(String, bool)> {
+    let mut ans: Vec<(String, bool)> = vec![];
+    ans.extend(core.builtins.keys().map(|k| (k.clone(), true)));
+    ans.extend(core.subst_builtins.keys().map(|k| (k.clone(), true)));
+    ans.extend(core.disabled_builtins.keys().map(|k| (k.clone(), false)));
+    ans.extend(core.disabled_subst_builtins.keys().map(|k| (k.clone(), false)));
+    ans.extend(KEYWORD_TOPICS.iter().map(|(_, t)| (t.to_string(), true)));
+    ans.sort();
+    ans.dedup_by(|a, b| a.0 == b.0);
+    ans
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?']) || (pattern.contains('[') && pattern.contains(']'))
+}
+
+fn matched_topics(core: &ShellCore, pattern: &str) -> Vec<String> {
+    /* words like `fi' and `esac' lead to the topic of their command */
+    if let Some((_, topic)) = KEYWORD_TOPICS.iter().find(|(w, t)| *w == pattern && w != t) {
+        return vec![topic.to_string()];
+    }
+
+    topics(core)
+        .into_iter()
+        .map(|(t, _)| t)
+        .filter(|t| match is_glob(pattern) {
+            true => glob::parse_and_compare(t, pattern, false),
+            false => t.starts_with(pattern),
+        })
+        .collect()
+}
+
+fn print_entry(entry: &Entry, flag: char) {
+    match flag {
+        'd' => println!("{} - {}", entry.name, entry.short()),
+        's' => println!("{}: {}", entry.name, entry.synopsis),
+        'm' => {
+            println!("NAME\n    {} - {}\n", entry.name, entry.short());
+            println!("SYNOPSIS\n    {}\n", entry.synopsis);
+            println!("DESCRIPTION");
+            entry.desc.iter().for_each(|line| println!("    {line}"));
+            println!("\nIMPLEMENTATION\n    {}\n", version());
+        }
+        _ => {
+            println!("{}: {}", entry.name, entry.synopsis);
+            entry.desc.iter().for_each(|line| println!("    {line}"));
+        }
+    }
+}
+
+fn version() -> String {
+    format!(
+        "Sushi shell (a.k.a. Sush), {} {} - {}",
+        i18n::fl("version"),
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_BUILD_PROFILE")
+    )
+}
+
+/* one line of the two column list; the synopsis is cut with `>' */
+fn column(synopsis: &str, enabled: bool, width: usize) -> String {
+    let mark = if enabled { ' ' } else { '*' };
+    let chars: Vec<char> = synopsis.chars().collect();
+    match chars.len() + 3 >= width {
+        true => format!("{mark}{}>", chars[..width.saturating_sub(3)].iter().collect::<String>()),
+        false => format!("{mark}{synopsis}"),
+    }
+}
+
+fn print_list(core: &mut ShellCore) {
+    println!("{}\n{}\n", version(), i18n::fl("help-header"));
+
+    let cols = core
+        .db
+        .get_param("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .filter(|c| *c > 6)
+        .unwrap_or(80);
+    let width = cols / 2;
+
+    let list: Vec<(String, bool)> = topics(core)
+        .into_iter()
+        .map(|(t, enabled)| (entry(core, &t).synopsis, enabled))
+        .collect();
+
+    let height = list.len().div_ceil(2);
+    for i in 0..height {
+        let left = column(&list[i].0, list[i].1, width);
+        match list.get(i + height) {
+            Some((s, enabled)) => {
+                let right = column(s, *enabled, width - 1);
+                println!("{left:width$}{right}", width = width);
+            }
+            None => println!("{left}"),
+        }
+    }
+}
+
+pub fn help(core: &mut ShellCore, args: &[String]) -> i32 {
+    let mut flag = ' ';
+    let mut i = 1;
+
+    while i < args.len() && args[i].starts_with('-') && args[i] != "-" {
+        if args[i] == "--" {
+            i += 1;
+            break;
+        }
+
+        for c in args[i][1..].chars() {
+            match c {
+                'd' | 'm' | 's' => flag = c,
+                _ => {
+                    error_(2, &args[0], &format!("-{c}: invalid option"), core);
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let patterns = &args[i..];
+    if patterns.is_empty() {
+        print_list(core);
+        return 0;
+    }
+
+    if is_glob(&patterns[0]) {
+        println!("{} `{}'\n", i18n::fl("help-matching"), patterns.join(", "));
+    }
+
+    let mut exit_status = 0;
+    for pattern in patterns {
+        let matched = matched_topics(core, pattern);
+        if matched.is_empty() {
+            let msg = format!(
+                "no help topics match `{pattern}'.  Try `help help' or `man -k {pattern}' or `info {pattern}'."
+            );
+            exit_status = error_(1, &args[0], &msg, core);
+        }
+
+        for topic in matched {
+            print_entry(&entry(core, &topic), flag);
+        }
+    }
+    exit_status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+
+    #[test]
+    fn every_builtin_has_help() {
+        let mut core = ShellCore::new();
+        core.set_builtins();
+
+        let mut names: Vec<String> = core.builtins.keys().cloned().collect();
+        names.extend(core.subst_builtins.keys().cloned());
+        for word in utils::RESERVED_WORDS {
+            let topic = KEYWORD_TOPICS.iter().find(|(w, _)| *w == word);
+            assert!(topic.is_some(), "no help topic for `{word}'");
+            names.push(topic.unwrap().1.to_string());
+        }
+
+        for name in names {
+            let id = message_id(&name);
+            assert!(i18n::fl_opt(&id).is_some(), "no synopsis of `{name}'");
+            let desc = i18n::fl_attr(&id, "desc").unwrap_or_default();
+            assert!(!desc.is_empty(), "no description of `{name}'");
+        }
+    }
+}
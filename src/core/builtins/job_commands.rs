@@ -3,28 +3,20 @@
 
 use libc;
 use crate::core::JobEntry;
+use crate::elements::command::simple::SimpleCommand;
+use crate::elements::io::pipe::Pipe;
 use crate::utils::arg;
-use crate::ShellCore;
-use crate::{signal, utils};
+use crate::{proc_ctrl, signal, ShellCore};
+use nix::errno::Errno;
 use nix::sys::signal::Signal;
 use nix::unistd;
 use nix::unistd::Pid;
-use std::{thread, time};
 //use std::sync::atomic::Ordering::Relaxed;
 
 fn pid_to_array_pos(pid: i32, jobs: &[JobEntry]) -> Option<usize> {
     (0..jobs.len()).find(|&i| jobs[i].pids[0].as_raw() == pid)
 }
 
-fn jobid_to_pos(id: usize, jobs: &mut [JobEntry]) -> Option<usize> {
-    for (i, job) in jobs.iter_mut().enumerate() {
-        if job.id == id {
-            return Some(i);
-        }
-    }
-    None
-}
-
 pub fn bg(core: &mut ShellCore, args: &[String]) -> i32 {
     let args = args.to_owned();
     if core.job_table.is_empty() {
@@ -41,10 +33,7 @@ pub fn bg(core: &mut ShellCore, args: &[String]) -> i32 {
     }
 
     let pos = match args.len() {
-        1 => {
-            let id = core.job_table_priority[0];
-            jobid_to_pos(id, &mut core.job_table)
-        }
+        1 => current_job_pos(core, &args[0]),
         2 => jobspec_to_array_pos(core, &args[0], &args[1]),
         _ => None,
     };
@@ -88,23 +77,14 @@ pub fn fg(core: &mut ShellCore, args: &[String]) -> i32 {
         return super::error_(1, &args[0], "-s: invalid option", core);
     }
 
-    let id = if args.len() == 1 {
-        if core.job_table_priority.is_empty() {
-            return 1;
-        }
-        core.job_table_priority[0]
-    } else if args.len() == 2 {
-        match jobspec_to_array_pos(core, &args[0], &args[1]) {
-            Some(pos) => core.job_table[pos].id,
-            None => return 1,
-        }
-    } else {
-        return 1;
+    let pos = match args.len() {
+        1 => current_job_pos(core, &args[0]),
+        2 => jobspec_to_array_pos(core, &args[0], &args[1]),
+        _ => None,
     };
-
-    let pos = match jobid_to_pos(id, &mut core.job_table) {
-        Some(i) => i,
-        _ => return 1,
+    let pos = match pos {
+        Some(p) => p,
+        None => return 1,
     };
 
     if core.job_table[pos].no_control {
@@ -143,180 +123,152 @@ pub fn fg(core: &mut ShellCore, args: &[String]) -> i32 {
     exit_status
 }
 
-fn jobspec_to_array_pos(core: &mut ShellCore, com: &str, jobspec: &str) -> Option<usize> {
-    let poss = jobspec_to_array_poss(core, jobspec);
-    if poss.is_empty() {
-        let msg = format!("{}: no such job", &jobspec);
-        super::error_(127, com, &msg, core);
-        return None;
-    } else if poss.len() > 1 {
-        let msg = format!(
-            "{}: ambiguous job spec",
-            jobspec.strip_prefix('%').unwrap_or(jobspec)
-        );
-        super::error_(127, com, &msg, core);
-        return None;
-    }
+/* accepts %%, %+, %-, %n, %str and %?str. The leading % can be omitted. */
+fn jobspec_to_array_poss(core: &ShellCore, jobspec: &str) -> Vec<usize> {
+    let s = jobspec.strip_prefix('%').unwrap_or(jobspec);
+    let priority = &core.job_table_priority;
 
-    Some(poss[0])
-}
+    let id = match s {
+        "" | "%" | "+" => priority.first().copied(),
+        "-" => priority.get(1).or(priority.first()).copied(),
+        _ => s.parse::<usize>().ok(),
+    };
 
-fn jobspec_to_array_poss(core: &mut ShellCore, jobspec: &str) -> Vec<usize> {
-    if jobspec.is_empty() {
-        return (0..core.job_table.len()).collect();
+    let jobs = core.job_table.iter().enumerate();
+    if id.is_some() || matches!(s, "" | "%" | "+" | "-") {
+        return jobs.filter(|(_, job)| Some(job.id) == id).map(|(i, _)| i).collect();
     }
 
-    if core.job_table.is_empty() {
-        return vec![];
+    match s.strip_prefix('?') {
+        Some(sub) => jobs.filter(|(_, job)| job.text.contains(sub)).map(|(i, _)| i).collect(),
+        None => jobs.filter(|(_, job)| job.text.starts_with(s)).map(|(i, _)| i).collect(),
     }
+}
 
-    let s = &jobspec[1..];
-    let mut ans = vec![];
-
-    if let Ok(n) = s.parse::<usize>() {
-        for (i, job) in core.job_table.iter_mut().enumerate() {
-            if n == job.id {
-                ans.push(i);
-            }
-        }
-    } else if s == "%" || s == "+" {
-        for (i, job) in core.job_table.iter_mut().enumerate() {
-            if job.id == core.job_table_priority[0] {
-                ans.push(i);
-            }
-        }
-    } else if s == "-" {
-        for (i, job) in core.job_table.iter_mut().enumerate() {
-            if core.job_table_priority.len() < 2 {
-                if job.id == core.job_table_priority[0] {
-                    ans.push(i);
-                }
-            } else if job.id == core.job_table_priority[1] {
-                ans.push(i);
-            }
-        }
-    } else if let Some(stripped) = s.strip_prefix('?') {
-        for (i, job) in core.job_table.iter_mut().enumerate() {
-            if job.text.contains(stripped) {
-                ans.push(i);
-            }
+fn jobspec_to_array_pos(core: &mut ShellCore, com: &str, jobspec: &str) -> Option<usize> {
+    let poss = jobspec_to_array_poss(core, jobspec);
+    match poss.len() {
+        1 => Some(poss[0]),
+        0 => {
+            let msg = format!("{}: no such job", &jobspec);
+            super::error_(1, com, &msg, core);
+            None
         }
-    } else {
-        for (i, job) in core.job_table.iter_mut().enumerate() {
-            if job.text.starts_with(s) {
-                ans.push(i);
-            }
+        _ => {
+            let msg = format!(
+                "{}: ambiguous job spec",
+                jobspec.strip_prefix('%').unwrap_or(jobspec)
+            );
+            super::error_(1, com, &msg, core);
+            None
         }
     }
-
-    ans
 }
 
-pub fn jobs(core: &mut ShellCore, args: &[String]) -> i32 {
-    let mut args = arg::dissolve_options(args);
-    if arg::consume_arg("-n", &mut args) {
-        core.jobtable_print_status_change();
-        return 0;
+fn current_job_pos(core: &mut ShellCore, com: &str) -> Option<usize> {
+    match jobspec_to_array_poss(core, "%+").first() {
+        Some(pos) => Some(*pos),
+        None => {
+            super::error_(1, com, "current: no such job", core);
+            None
+        }
     }
+}
 
-    let jobspecs = arg::consume_starts_with("%", &mut args);
-    let jobspec = match jobspecs.last() {
-        Some(s) => s.clone(),
-        None => String::new(),
-    };
-
-    if core.job_table.is_empty() && jobspec.is_empty() {
+fn jobs_x(core: &mut ShellCore, args: &[String]) -> i32 {
+    if args.is_empty() {
         return 0;
     }
 
-    let poss = jobspec_to_array_poss(core, &jobspec);
-
-    if poss.is_empty() {
-        let msg = format!("{}: no such job", &jobspec);
-        return super::error_(127, "jobs", &msg, core);
+    let mut words = vec![];
+    for a in args {
+        let poss = match a.starts_with('%') {
+            true => jobspec_to_array_poss(core, a),
+            false => vec![],
+        };
+        match poss.len() {
+            1 => words.push(core.job_table[poss[0]].solve_pgid().to_string()),
+            _ => words.push(a.clone()),
+        }
     }
-    if poss.len() > 1 && !jobspec.is_empty() {
-        let msg = format!(
-            "{}: ambiguous job spec",
-            jobspec.strip_prefix('%').unwrap_or(&jobspec)
-        );
-        super::error_(127, "jobs", &msg, core);
-        let msg = format!("{}: no such job", &jobspec);
-        return super::error_(127, "jobs", &msg, core);
+
+    /* builtins and functions are also run through the simple command */
+    let mut command = SimpleCommand::default();
+    let mut pipe = Pipe::new("".to_string());
+    command.args = words;
+    if let Ok(pid) = command.exec_command(core, &mut pipe) {
+        proc_ctrl::wait_pipeline(core, vec![pid], false, false);
     }
+    core.db.exit_status
+}
 
-    if arg::consume_arg("-p", &mut args) {
-        for id in poss {
-            core.job_table[id].print_p();
-        }
-        return 0;
+pub fn jobs(core: &mut ShellCore, args: &[String]) -> i32 {
+    if args.len() > 1 && args[1] == "-x" {
+        return jobs_x(core, &args[2..]);
     }
 
-    if !jobspec.is_empty() {
-        let l_opt = arg::consume_arg("-l", &mut args);
-        let r_opt = arg::consume_arg("-r", &mut args);
-        let s_opt = arg::consume_arg("-s", &mut args);
-        if core.job_table[poss[0]].print(&core.job_table_priority, l_opt, r_opt, s_opt, true) {
-            remove(core, poss[0]);
-        }
+    let mut args = arg::dissolve_options(args);
+    if arg::consume_arg("-n", &mut args) {
+        core.jobtable_print_status_change();
         return 0;
     }
 
-    print(core, &args);
-    0
-}
+    let l_opt = arg::consume_arg("-l", &mut args);
+    let r_opt = arg::consume_arg("-r", &mut args);
+    let s_opt = arg::consume_arg("-s", &mut args);
+    let p_opt = arg::consume_arg("-p", &mut args);
 
-fn get_priority(core: &mut ShellCore, pos: usize) -> usize {
-    let id = core.job_table[pos].id;
-    for i in 0..core.job_table_priority.len() {
-        if core.job_table_priority[i] == id {
-            return i;
+    let mut exit_status = 0;
+    let mut poss = vec![];
+    match args.len() {
+        1 => poss = (0..core.job_table.len()).collect(),
+        _ => {
+            for jobspec in &args[1..] {
+                match jobspec_to_array_pos(core, "jobs", jobspec) {
+                    Some(pos) => poss.push(pos),
+                    None => {
+                        if jobspec_to_array_poss(core, jobspec).len() > 1 {
+                            let msg = format!("{}: no such job", &jobspec);
+                            super::error_(1, "jobs", &msg, core);
+                        }
+                        exit_status = 1;
+                    }
+                }
+            }
         }
     }
 
-    core.job_table.len()
-}
-
-fn print(core: &mut ShellCore, args: &[String]) {
-    let l_opt = arg::has_option("-l", args);
-    let r_opt = arg::has_option("-r", args);
-    let s_opt = arg::has_option("-s", args);
-
     let mut rem = vec![];
-    for id in 0..core.job_table.len() {
-        if core.job_table[id].print(&core.job_table_priority, l_opt, r_opt, s_opt, true) {
-            rem.push(id);
+    for pos in poss {
+        if p_opt {
+            core.job_table[pos].print_p();
+        } else if core.job_table[pos].print(&core.job_table_priority, l_opt, r_opt, s_opt, true) {
+            rem.push(pos);
         }
     }
 
+    rem.sort();
+    rem.dedup();
     for pos in rem.into_iter().rev() {
         remove(core, pos);
     }
+    exit_status
 }
 
-fn remove_coproc(core: &mut ShellCore, pos: usize) {
-    if let Some(name) = &core.job_table[pos].coproc_name {
-        let _ = core.db.unset(&name, None, false);
-        let _ = core.db.unset(&(name.to_owned() + "_PID"), None, false);
-
-        if let Ok(fd0) = core.db.get_elem(&name, "0") {
-            if let Ok(n) = fd0.parse::<i32>() {
-                let _ = unsafe{libc::close(n)};
-            }
-        }
-        if let Ok(fd1) = core.db.get_elem(&name, "1") {
-            if let Ok(n) = fd1.parse::<i32>() {
-                let _ = unsafe{libc::close(n)};
-            }
+fn get_priority(core: &mut ShellCore, pos: usize) -> usize {
+    let id = core.job_table[pos].id;
+    for i in 0..core.job_table_priority.len() {
+        if core.job_table_priority[i] == id {
+            return i;
         }
-
-        let _ = core.db.unset(&(name), None, false);
     }
+
+    core.job_table.len()
 }
 
 fn remove(core: &mut ShellCore, pos: usize) {
     let job_id = core.job_table[pos].id;
-    remove_coproc(core, pos);
+    core.close_coproc(pos);
     core.job_table.remove(pos);
     core.job_table_priority.retain(|id| *id != job_id);
 }
@@ -344,44 +296,13 @@ fn wait_next(
         return (127, false);
     }
 
-    let mut exit_status = 0;
-    let mut drop = 0;
-    let mut end = false;
-    let mut pid = String::new();
-    let mut remove_job = false;
-
-    loop {
-        /*
-        dbg!("H");
-        if core.sigint.load(Relaxed) {
-            dbg!("!!!");
-        }*/
-
-        thread::sleep(time::Duration::from_millis(10)); //0.1秒周期に変更
-        for (i, job) in core.job_table.iter_mut().enumerate() {
-            if !ids.contains(&i) && !ids.is_empty() {
-                continue;
-            }
-
-            if let Ok(es) = job.update_status(false, true) {
-                if job.display_status == "Done"
-                    || job.display_status == "Killed"
-                    || (job.display_status == "Stopped" && !f_opt)
-                {
-                    exit_status = es;
-                    drop = i;
-                    end = true;
-                    remove_job = job.display_status == "Done" || job.display_status == "Killed";
-                    pid = job.pids[0].to_string();
-                    break;
-                }
-            }
-        }
-
-        if end {
-            break;
-        }
-    }
+    let (drop, exit_status) = match core.wait_any_job(ids, f_opt) {
+        Some(found) => found,
+        None => return (130, true),
+    };
+    let job = &core.job_table[drop];
+    let remove_job = job.display_status == "Done" || job.display_status == "Killed";
+    let pid = job.pids[0].to_string();
 
     if let Some(var) = var_name {
         let _ = core.db.unset(var, None, false);
@@ -466,9 +387,7 @@ fn wait_all(core: &mut ShellCore) -> i32 {
     for pos in 0..core.job_table.len() {
         match core.job_table[pos].update_status(true, false) {
             Ok(n) => {
-                if core.job_table[pos].display_status == "Done"
-                    || core.job_table[pos].display_status == "Killed"
-                {
+                if core.job_table[pos].display_status != "Stopped" {
                     remove_list.push(pos);
                 }
                 exit_status = n;
@@ -553,88 +472,209 @@ pub fn wait(core: &mut ShellCore, args: &[String]) -> i32 {
     1
 }
 
-/* TODO: implement original kill */
-pub fn kill(core: &mut ShellCore, args: &[String]) -> i32 {
-    let mut args = args.to_owned();
-    //let mut args = arg::dissolve_options(args);
-    let path = utils::get_command_path(&args[0], core);
+fn kill_usage() -> i32 {
+    eprintln!("kill: usage: kill [-s sigspec | -n signum | -sigspec] pid | jobspec ... or kill -l [sigspec]");
+    2
+}
 
-    match path.is_empty() {
-        true => return 1,
-        false => args[0] = path,
+fn kill_list(core: &mut ShellCore, args: &[String]) -> i32 {
+    if args.is_empty() {
+        signal::print_signal_list();
+        return 0;
     }
 
-    if args.len() >= 3 && args[2].starts_with("%") {
-        match jobspec_to_array_pos(core, &args[0], &args[2]) {
-            Some(id) => args[2] = core.job_table[id].pids[0].to_string(),
-            None => return 1,
-        }
-    }
+    let mut exit_status = 0;
+    for a in args {
+        let name = match a.parse::<i32>() {
+            Ok(0) => Some("EXIT".to_string()),
+            Ok(n) if n > 128 => signal::signal_name(n - 128),
+            Ok(n) => signal::signal_name(n),
+            Err(_) => signal::signal_number(a).map(|n| n.to_string()),
+        };
 
-    let com = args[0].to_string();
-    for arg in args.iter_mut() {
-        if arg == "-n" {
-            *arg = "-s".to_string();
-        }
-        if arg.starts_with("%") {
-            if let Some(pos) = jobspec_to_array_pos(core, &com, arg) {
-                *arg = core.job_table[pos].pids[0].to_string();
-            } else {
-                let msg = format!("{}: no such job", &arg);
-                return super::error_(127, "jobs", &msg, core);
+        match name {
+            Some(name) => println!("{}", name.strip_prefix("SIG").unwrap_or(&name)),
+            None => {
+                let msg = format!("{a}: invalid signal specification");
+                exit_status = super::error_(1, "kill", &msg, core);
             }
         }
     }
+    exit_status
+}
 
-    /*
-    args.insert(0, "eval".to_string());
-    super::eval(core, &args)
-    */
-    super::run_external(core, &args, |es| es > 0)
+fn send_signal(pid: i32, sig: i32) -> Result<(), Errno> {
+    Errno::result(unsafe { libc::kill(pid, sig) }).map(drop)
 }
 
-pub fn disown(core: &mut ShellCore, args: &[String]) -> i32 {
-    let mut args = arg::dissolve_options(args);
-    let h_opt = arg::consume_arg("-h", &mut args);
-    let _r_opt = arg::consume_arg("-r", &mut args); //TODO: implement
+fn kill_job(core: &mut ShellCore, jobspec: &str, sig: i32) -> bool {
+    let pos = match jobspec_to_array_pos(core, "kill", jobspec) {
+        Some(pos) => pos,
+        None => return false,
+    };
 
-    if args.len() == 1 {
-        let ids = jobspec_to_array_poss(core, "%%");
+    let pids = core.job_table[pos].pids.clone();
+    let pgid = core.job_table[pos].solve_pgid();
+    let result = match pgid.as_raw() {
+        0 => Err(Errno::ESRCH),
+        _ if pgid == unistd::getpgrp() => pids.iter().try_for_each(|p| send_signal(p.as_raw(), sig)),
+        _ => send_signal(-pgid.as_raw(), sig),
+    };
+
+    if let Err(e) = result {
+        let msg = format!("{}: {}", jobspec, e.desc());
+        super::error_(1, "kill", &msg, core);
+        return false;
+    }
+    true
+}
 
-        if ids.len() == 1 {
-            remove_coproc(core, ids[0]);
-            core.job_table.remove(ids[0]);
-            core.job_table_priority.remove(0);
-            return 0;
+fn kill_pid(core: &mut ShellCore, arg: &str, sig: i32) -> bool {
+    let pid = match arg.parse::<i32>() {
+        Ok(pid) => pid,
+        Err(_) => {
+            let msg = format!("{arg}: arguments must be process or job IDs");
+            super::error_(1, "kill", &msg, core);
+            return false;
         }
+    };
 
-        return 1;
+    if let Err(e) = send_signal(pid, sig) {
+        let msg = format!("({}) - {}", pid, e.desc());
+        super::error_(1, "kill", &msg, core);
+        return false;
     }
+    true
+}
 
-    if args.len() == 2 && args[1] == "-a" {
-        core.job_table.clear();
-        core.job_table_priority.clear();
-        return 0;
+pub fn kill(core: &mut ShellCore, args: &[String]) -> i32 {
+    let mut args = args[1..].to_vec();
+    let mut sig = libc::SIGTERM;
+
+    let spec = match args.first().map(|a| a.as_str()) {
+        None => return kill_usage(),
+        Some("-l") | Some("-L") => return kill_list(core, &args[1..]),
+        Some("-s") | Some("-n") => match args.len() > 1 {
+            true => {
+                args.remove(0);
+                Some(args.remove(0))
+            }
+            false => {
+                let msg = format!("{}: option requires an argument", &args[0]);
+                super::error_(2, "kill", &msg, core);
+                return kill_usage();
+            }
+        },
+        Some("--") => None,
+        Some(a) if a.len() > 1 && a.starts_with('-') => Some(args.remove(0)[1..].to_string()),
+        _ => None,
+    };
+
+    if let Some(spec) = spec {
+        sig = match signal::signal_number(&spec) {
+            Some(n) => n,
+            None => {
+                let msg = format!("{spec}: invalid signal specification");
+                return super::error_(1, "kill", &msg, core);
+            }
+        };
+    }
+
+    if args.first().is_some_and(|a| a == "--") {
+        args.remove(0);
+    }
+    if args.is_empty() {
+        return kill_usage();
     }
 
+    let mut any_succeeded = false;
+    for arg in &args {
+        any_succeeded |= match arg.starts_with('%') {
+            true => kill_job(core, arg, sig),
+            false => kill_pid(core, arg, sig),
+        };
+    }
+
+    match any_succeeded {
+        true => 0,
+        false => 1,
+    }
+}
+
+pub fn disown(core: &mut ShellCore, args: &[String]) -> i32 {
+    let mut args = arg::dissolve_options(args);
+    let h_opt = arg::consume_arg("-h", &mut args);
+    let r_opt = arg::consume_arg("-r", &mut args);
+    let a_opt = arg::consume_arg("-a", &mut args);
+
     for a in &args[1..] {
         if a.starts_with("-") {
             let msg = format!("{}: invalid option", &a);
-            super::error_(127, &args[0], &msg, core);
+            super::error_(2, &args[0], &msg, core);
             eprintln!("disown: usage: disown [-h] [-ar] [jobspec ... | pid ...]");
-            return 127;
+            return 2;
+        }
+    }
+
+    let mut exit_status = 0;
+    let mut poss = vec![];
+    if args.len() == 1 && (a_opt || r_opt) {
+        poss = (0..core.job_table.len()).collect();
+    } else if args.len() == 1 {
+        match current_job_pos(core, &args[0]) {
+            Some(pos) => poss.push(pos),
+            None => return 1,
         }
     }
 
     for a in &args[1..] {
-        if let Some(pos) = jobspec_to_array_pos(core, &args[0], a) {
-            if h_opt {
-                //TODO: to make each job doesn't stop by SIGHUP
-            } else {
-                remove(core, pos);
+        let pos = match a.parse::<i32>() {
+            Ok(pid) => pid_to_array_pos(pid, &core.job_table),
+            Err(_) => jobspec_to_array_pos(core, &args[0], a),
+        };
+
+        match pos {
+            Some(pos) => poss.push(pos),
+            None => {
+                if a.parse::<i32>().is_ok() {
+                    let msg = format!("{}: no such job", &a);
+                    super::error_(1, &args[0], &msg, core);
+                }
+                exit_status = 1;
             }
         }
     }
 
-    0
+    if r_opt {
+        poss.retain(|p| core.job_table[*p].display_status == "Running");
+    }
+
+    if h_opt {
+        //TODO: to make each job doesn't stop by SIGHUP
+        return exit_status;
+    }
+
+    poss.sort();
+    poss.dedup();
+    for pos in poss.into_iter().rev() {
+        remove(core, pos);
+    }
+    exit_status
+}
+
+pub fn suspend(core: &mut ShellCore, args: &[String]) -> i32 {
+    let mut args = arg::dissolve_options(args);
+    let f_opt = arg::consume_arg("-f", &mut args);
+
+    if !core.db.flags.contains('m') {
+        return super::error_(1, &args[0], "cannot suspend: no job control", core);
+    }
+    if !f_opt && core.shopts.query("login_shell") {
+        return super::error_(1, &args[0], "cannot suspend a login shell", core);
+    }
+
+    match nix::sys::signal::killpg(unistd::getpgrp(), Signal::SIGSTOP) {
+        Ok(()) => 0,
+        Err(e) => super::error_(1, &args[0], e.desc(), core),
+    }
 }
@@ -0,0 +1,259 @@
+//SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
+//SPDX-License-Identifier: BSD-3-Clause
+
+use super::error_;
+use crate::{utils, ShellCore};
+use nix::errno::Errno;
+use nix::unistd;
+use nix::unistd::Whence;
+use std::os::fd::{BorrowedFd, RawFd};
+
+const USAGE: &str = "mapfile: usage: mapfile [-d delim] [-n count] [-O origin] [-s count] [-t] [-u fd] [-C callback] [-c quantum] [array]";
+
+struct MapfileOptions {
+    delim: u8,
+    count: usize,
+    origin: Option<isize>,
+    skip: usize,
+    trim: bool,
+    fd: RawFd,
+    callback: Option<String>,
+    quantum: usize,
+}
+
+impl Default for MapfileOptions {
+    fn default() -> Self {
+        Self {
+            delim: b'\n',
+            count: 0,
+            origin: None,
+            skip: 0,
+            trim: false,
+            fd: 0,
+            callback: None,
+            quantum: 5000,
+        }
+    }
+}
+
+fn borrow(fd: RawFd) -> BorrowedFd<'static> {
+    unsafe { BorrowedFd::borrow_raw(fd) }
+}
+
+/* reads lines from an fd without consuming more than needed */
+struct FdReader {
+    fd: RawFd,
+    buf: Vec<u8>,
+    pos: usize,
+    unbuffered: bool,
+}
+
+impl FdReader {
+    fn new(fd: RawFd, count: usize) -> Self {
+        let seekable = unistd::lseek(borrow(fd), 0, Whence::SeekCur).is_ok();
+        Self {
+            fd,
+            buf: vec![],
+            pos: 0,
+            unbuffered: count > 0 && !seekable,
+        }
+    }
+
+    fn fill(&mut self) -> bool {
+        let mut chunk = vec![0u8; if self.unbuffered { 1 } else { 4096 }];
+        loop {
+            match unistd::read(borrow(self.fd), &mut chunk) {
+                Ok(0) => return false,
+                Ok(n) => {
+                    self.buf.drain(..self.pos);
+                    self.pos = 0;
+                    self.buf.extend_from_slice(&chunk[..n]);
+                    return true;
+                }
+                Err(Errno::EINTR) => continue,
+                Err(_) => return false,
+            }
+        }
+    }
+
+    fn read_line(&mut self, delim: u8) -> Option<Vec<u8>> {
+        let mut searched = self.pos;
+        loop {
+            if let Some(p) = self.buf[searched..].iter().position(|b| *b == delim) {
+                let end = searched + p + 1;
+                let line = self.buf[self.pos..end].to_vec();
+                self.pos = end;
+                return Some(line);
+            }
+
+            searched = self.buf.len() - self.pos;
+            if !self.fill() {
+                break;
+            }
+        }
+
+        if self.pos == self.buf.len() {
+            return None;
+        }
+        let line = self.buf[self.pos..].to_vec();
+        self.pos = self.buf.len();
+        Some(line)
+    }
+
+    /* gives back the bytes read ahead */
+    fn rewind(&mut self) {
+        let rest = (self.buf.len() - self.pos) as i64;
+        if rest > 0 {
+            let _ = unistd::lseek(borrow(self.fd), -rest, Whence::SeekCur);
+        }
+    }
+}
+
+fn usage_error(core: &mut ShellCore, msg: &str) -> i32 {
+    error_(2, "mapfile", msg, core);
+    eprintln!("{USAGE}");
+    2
+}
+
+fn parse_number(core: &mut ShellCore, s: &str, what: &str) -> Result<usize, i32> {
+    match s.parse::<usize>() {
+        Ok(n) => Ok(n),
+        Err(_) => Err(error_(1, "mapfile", &format!("{s}: invalid {what}"), core)),
+    }
+}
+
+fn set_option(core: &mut ShellCore, opts: &mut MapfileOptions, opt: char, value: &str) -> Result<(), i32> {
+    match opt {
+        'd' => opts.delim = value.bytes().next().unwrap_or(0),
+        'n' => opts.count = parse_number(core, value, "line count")?,
+        'O' => opts.origin = Some(parse_number(core, value, "array origin")? as isize),
+        's' => opts.skip = parse_number(core, value, "line count")?,
+        'C' => opts.callback = Some(value.to_string()),
+        'c' => match value.parse::<usize>() {
+            Ok(n) if n > 0 => opts.quantum = n,
+            _ => {
+                let msg = format!("{value}: invalid callback quantum");
+                return Err(error_(1, "mapfile", &msg, core));
+            }
+        },
+        _ => {
+            let fd = parse_number(core, value, "file descriptor specification")? as RawFd;
+            if nix::fcntl::fcntl(borrow(fd), nix::fcntl::FcntlArg::F_GETFD).is_err() {
+                let msg = format!("{value}: invalid file descriptor: Bad file descriptor");
+                return Err(error_(1, "mapfile", &msg, core));
+            }
+            opts.fd = fd;
+        }
+    }
+    Ok(())
+}
+
+fn parse_options(core: &mut ShellCore, args: &[String]) -> Result<(MapfileOptions, String), i32> {
+    let mut opts = MapfileOptions::default();
+    let mut i = 1;
+
+    while i < args.len() {
+        let arg = &args[i];
+        if arg == "--" {
+            i += 1;
+            break;
+        }
+        if !arg.starts_with('-') || arg == "-" {
+            break;
+        }
+
+        for (pos, c) in arg.char_indices().skip(1) {
+            if c == 't' {
+                opts.trim = true;
+                continue;
+            }
+            if !"dnOsuCc".contains(c) {
+                return Err(usage_error(core, &format!("-{c}: invalid option")));
+            }
+
+            let value = match &arg[pos + 1..] {
+                "" if i + 1 < args.len() => {
+                    i += 1;
+                    args[i].clone()
+                }
+                "" => return Err(usage_error(core, &format!("-{c}: option requires an argument"))),
+                rest => rest.to_string(),
+            };
+            set_option(core, &mut opts, c, &value)?;
+            break;
+        }
+        i += 1;
+    }
+
+    let name = args.get(i).cloned().unwrap_or("MAPFILE".to_string());
+    Ok((opts, name))
+}
+
+fn run_callback(core: &mut ShellCore, callback: &str, index: isize, line: &str) {
+    let quoted = line.replace('\'', "'\\''");
+    let script = format!("{callback} {index} '{quoted}'");
+    super::eval(core, &["eval".to_string(), script]);
+}
+
+pub fn mapfile(core: &mut ShellCore, args: &[String]) -> i32 {
+    let (opts, name) = match parse_options(core, args) {
+        Ok(ans) => ans,
+        Err(exit_status) => return exit_status,
+    };
+
+    if !utils::is_name(&name, core) {
+        let msg = format!("`{name}': not a valid identifier");
+        return error_(1, "mapfile", &msg, core);
+    }
+    if core.db.is_assoc(&name) {
+        let msg = format!("{name}: not an indexed array");
+        return error_(1, "mapfile", &msg, core);
+    }
+
+    if opts.origin.is_none() {
+        let i_flag = core.db.has_flag(&name, 'i');
+        if let Err(e) = core.db.init_array(&name, Some(vec![]), None, i_flag) {
+            e.print(core);
+            return 1;
+        }
+    }
+
+    let mut reader = FdReader::new(opts.fd, opts.count);
+    for _ in 0..opts.skip {
+        if reader.read_line(opts.delim).is_none() {
+            break;
+        }
+    }
+
+    let mut index = opts.origin.unwrap_or(0);
+    let mut line_count = 0;
+    while opts.count == 0 || line_count < opts.count {
+        let mut line = match reader.read_line(opts.delim) {
+            Some(line) => line,
+            None => break,
+        };
+        if opts.trim && line.last() == Some(&opts.delim) {
+            line.pop();
+        }
+        let line = String::from_utf8_lossy(&line).to_string();
+
+        line_count += 1;
+        match &opts.callback {
+            Some(callback) if line_count % opts.quantum == 0 => {
+                run_callback(core, callback, index, &line)
+            }
+            _ => {}
+        }
+
+        if let Err(e) = core.db.set_array_elem(&name, &line, index, None, false) {
+            e.print(core);
+            return 1;
+        }
+        index += 1;
+    }
+
+    if opts.count > 0 {
+        reader.rewind();
+    }
+    0
+}
@@ -61,9 +61,13 @@ pub fn set_short_options(core: &mut ShellCore, args: &mut Vec<String>) {
         ('C', "noclobber"),
         ('a', "allexport"),
         ('B', "braceexpand"),
+        ('b', "notify"),
+        ('P', "physical"),
         ('f', ""),
         ('u', ""),
         ('e', ""),
+        ('E', "errtrace"),
+        ('T', "functrace"),
         ('r', ""),
         ('H', ""),
         ('x', ""),
@@ -93,18 +97,20 @@ pub fn set_short_options(core: &mut ShellCore, args: &mut Vec<String>) {
 fn set_bash_flags(core: &mut ShellCore, args: &[String]) {
     let positive = args[1] == "-o";
 
-    if args[2] == "monitor" {
-        if positive && !core.db.flags.contains('m') {
-            core.db.flags.push('m');
-        } else if !positive {
-            core.db.flags.retain(|f| f != 'm');
-        }
-    }else if args[2] == "allexport" {
-        if positive && !core.db.flags.contains('a') {
-            core.db.flags.push('a');
-        } else if !positive {
-            core.db.flags.retain(|f| f != 'a');
-        }
+    let flag = match args[2].as_str() {
+        "monitor" => 'm',
+        "allexport" => 'a',
+        "errtrace" => 'E',
+        "functrace" => 'T',
+        "notify" => 'b',
+        "physical" => 'P',
+        _ => return,
+    };
+
+    if positive && !core.db.flags.contains(flag) {
+        core.db.flags.push(flag);
+    } else if !positive {
+        core.db.flags.retain(|f| f != flag);
     }
 }
 
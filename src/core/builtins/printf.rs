@@ -1,446 +1,950 @@
 //SPDX-FileCopyrightText: 2024 Ryuichi Ueda <ryuichiueda@gmail.com>
 //SPDX-License-Identifier: BSD-3-Clause
 
+use super::error_;
 use crate::elements::substitution::Substitution;
-use crate::error::arith::ArithError;
-use crate::error::exec::ExecError;
-use crate::{error, Feeder, ShellCore};
+use crate::utils::clock;
+use crate::{Feeder, ShellCore};
+use nix::errno::Errno;
+use std::env;
+use std::ffi::CString;
 use std::io::{stdout, Write};
 
+const USAGE: &str = "printf: usage: printf [-v var] format [arguments]";
+const CONVERSIONS: &str = "diouxXfFeEgGaAcsbqQT";
+const LENGTH_MODIFIERS: &str = "hjlLtz";
+
+#[derive(Debug, Clone, Copy)]
+enum Count {
+    Num(usize),
+    Star,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Spec {
+    flags: String,
+    width: Option<Count>,
+    precision: Option<Count>,
+    conv: char,
+    time_fmt: String,
+}
+
 #[derive(Debug, Clone)]
 enum PrintfToken {
-    B(String),
-    DI(String),
-    F(String),
-    O(String, bool),
-    S(String),
-    U(String),
-    X(String, bool),
-    LargeX(String, bool),
-    Q,
-    Other(String),
-    Normal(String),
-    EscapedChar(char),
+    Text(Vec<u8>),
+    Conversion(Spec),
+    BadEscape(Vec<u8>, String),
+    Invalid(String),
 }
 
-impl PrintfToken {
-    fn continue_(&self) -> bool {
-        !matches!(self, Self::Normal(_) | Self::EscapedChar(_))
-    }
+/* a conversion whose width and precision are resolved */
+struct Field {
+    left: bool,
+    zero: bool,
+    plus: bool,
+    space: bool,
+    alt: bool,
+    width: usize,
+    precision: Option<usize>,
+    conv: char,
+}
 
-    fn to_int(s: &String) -> Result<isize, ExecError> {
-        if s.starts_with("'") && s.len() > 1 {
-            let ch = match s.chars().nth(1) {
-                Some(n) => n,
-                None => return Err(ExecError::Other("Invalid char: ".to_owned() + s)),
-            };
-            return Ok(ch as isize);
+impl Field {
+    fn pad(&self, body: Vec<u8>) -> Vec<u8> {
+        if body.len() >= self.width {
+            return body;
         }
 
-        match s.parse::<isize>() {
-            Ok(n) => Ok(n),
-            Err(_) => Err(ArithError::InvalidNumber(s.to_string()).into()),
+        let spaces = vec![b' '; self.width - body.len()];
+        match self.left {
+            true => [body, spaces].concat(),
+            false => [spaces, body].concat(),
         }
     }
 
-    fn to_float(s: &String) -> Result<f64, ExecError> {
-        match s.parse::<f64>() {
-            Ok(n) => Ok(n),
-            Err(_) => Err(ArithError::InvalidNumber(s.to_string()).into()),
+    /* sign, prefix like 0x, zeros and digits */
+    fn pad_number(&self, sign: &str, prefix: &str, digits: &str, zero_ok: bool) -> Vec<u8> {
+        let len = sign.len() + prefix.len() + digits.len();
+        if self.zero && zero_ok && !self.left && len < self.width {
+            let zeros = "0".repeat(self.width - len);
+            return format!("{sign}{prefix}{zeros}{digits}").into_bytes();
         }
+        self.pad(format!("{sign}{prefix}{digits}").into_bytes())
     }
 
-    //TODO: implement!
-    fn padding_float(_: &mut String, mut _fmt: String) {
-        if _fmt.is_empty() {}
+    fn sign(&self, negative: bool) -> &'static str {
+        match (negative, self.plus, self.space) {
+            (true, _, _) => "-",
+            (false, true, _) => "+",
+            (false, false, true) => " ",
+            _ => "",
+        }
     }
 
-    fn padding(s: &mut String, mut fmt: String, is_int: bool) {
-        if fmt.is_empty() {
-            return;
+    fn truncate(&self, mut s: Vec<u8>) -> Vec<u8> {
+        if let Some(p) = self.precision {
+            s.truncate(p);
         }
+        s
+    }
 
-        let mut right = false;
-        let mut padding = ' ';
+    fn signed(&self, n: i64) -> Vec<u8> {
+        let digits = self.digits(n.unsigned_abs());
+        self.pad_number(self.sign(n < 0), "", &digits, self.precision.is_none())
+    }
 
-        if fmt.starts_with("-") {
-            fmt.remove(0);
-            right = true;
+    fn unsigned(&self, n: u64) -> Vec<u8> {
+        let mut digits = self.digits(n);
+        let prefix = match (self.alt, self.conv) {
+            (true, 'o') if !digits.starts_with('0') => {
+                digits.insert(0, '0');
+                ""
+            }
+            (true, 'x') if n != 0 => "0x",
+            (true, 'X') if n != 0 => "0X",
+            _ => "",
+        };
+        self.pad_number("", prefix, &digits, self.precision.is_none())
+    }
+
+    fn digits(&self, n: u64) -> String {
+        let digits = match self.conv {
+            'o' => format!("{n:o}"),
+            'x' => format!("{n:x}"),
+            'X' => format!("{n:X}"),
+            _ => n.to_string(),
+        };
+
+        match self.precision {
+            Some(0) if n == 0 => String::new(),
+            Some(p) if p > digits.len() => "0".repeat(p - digits.len()) + &digits,
+            _ => digits,
         }
-        if fmt.starts_with("0") {
-            padding = fmt.remove(0);
+    }
+
+    fn float(&self, f: f64) -> Vec<u8> {
+        let sign = self.sign(f.is_sign_negative());
+        let upper = self.conv.is_ascii_uppercase();
+        let a = f.abs();
+
+        let (prefix, body) = match self.conv.to_ascii_lowercase() {
+            _ if f.is_infinite() => ("", "inf".to_string()),
+            _ if f.is_nan() => ("", "nan".to_string()),
+            'e' => ("", fmt_e(a, self.precision.unwrap_or(6), self.alt)),
+            'g' => ("", fmt_g(a, self.precision.unwrap_or(6), self.alt)),
+            'a' => ("0x", fmt_a(a, self.precision, self.alt)),
+            _ => ("", fmt_f(a, self.precision.unwrap_or(6), self.alt)),
+        };
+
+        match upper {
+            true => self.pad_number(
+                sign,
+                &prefix.to_uppercase(),
+                &body.to_uppercase(),
+                f.is_finite(),
+            ),
+            false => self.pad_number(sign, prefix, &body, f.is_finite()),
         }
+    }
+}
+
+fn fmt_f(f: f64, precision: usize, alt: bool) -> String {
+    let mut ans = format!("{f:.precision$}");
+    if alt && precision == 0 {
+        ans.push('.');
+    }
+    ans
+}
 
-        if !is_int {
-            padding = ' ';
+fn fmt_e(f: f64, precision: usize, alt: bool) -> String {
+    let s = format!("{f:.precision$e}");
+    let (mantissa, exp) = s.split_once('e').unwrap_or((&s, "0"));
+    let exp = exp.parse::<i32>().unwrap_or(0);
+    let point = if alt && precision == 0 { "." } else { "" };
+    let sign = if exp < 0 { '-' } else { '+' };
+    format!("{mantissa}{point}e{sign}{:02}", exp.abs())
+}
+
+fn fmt_g(f: f64, precision: usize, alt: bool) -> String {
+    let p = precision.max(1);
+    let exp = match f == 0.0 {
+        true => 0,
+        false => {
+            let s = format!("{f:.0$e}", p - 1);
+            s.split_once('e')
+                .and_then(|(_, e)| e.parse::<i32>().ok())
+                .unwrap_or(0)
         }
+    };
 
-        let len = fmt.parse::<usize>().unwrap_or(0);
-        if right {
-            while s.len() < len {
-                s.push(' ');
-            }
-            return;
+    let ans = match exp < -4 || exp >= p as i32 {
+        true => fmt_e(f, p - 1, alt),
+        false => fmt_f(f, (p as i32 - 1 - exp) as usize, alt),
+    };
+    if alt {
+        return ans;
+    }
+
+    /* remove the trailing zeros of the fraction */
+    let (num, exp) = match ans.find('e') {
+        Some(pos) => ans.split_at(pos),
+        None => (ans.as_str(), ""),
+    };
+    match num.contains('.') {
+        true => num.trim_end_matches('0').trim_end_matches('.').to_string() + exp,
+        false => ans.clone(),
+    }
+}
+
+/* Hexadecimal notation with a leading digit of four bits like bash's
+ * long double output. The value only has the precision of f64, so the
+ * last digits can differ from bash (0xc.cccccccccccdp-7 for 0.1). */
+fn fmt_a(f: f64, precision: Option<usize>, alt: bool) -> String {
+    let bits = f.to_bits();
+    let exp_bits = ((bits >> 52) & 0x7ff) as i32;
+    let frac = bits & ((1 << 52) - 1);
+
+    let (mantissa, mut exp) = match (exp_bits, frac) {
+        (0, 0) => (0, 3),
+        (0, _) => {
+            let shift = frac.leading_zeros() - 11;
+            (frac << shift, -1022 - shift as i32)
         }
+        _ => (frac | (1 << 52), exp_bits - 1023),
+    };
 
-        if is_int && s.starts_with("-") && padding == '0' {
-            while s.len() < len {
-                s.insert(1, '0');
+    let m64 = mantissa << 11;
+    let mut lead = m64 >> 60;
+    let rest = m64 & ((1 << 60) - 1);
+    exp -= 3;
+
+    let digits = match precision {
+        None => format!("{rest:015x}").trim_end_matches('0').to_string(),
+        Some(p) if p >= 15 => format!("{rest:015x}") + &"0".repeat(p - 15),
+        Some(p) => {
+            let shift = 4 * (15 - p) as u32;
+            let half = 1u64 << (shift - 1);
+            let (mut q, r) = (rest >> shift, rest & ((1u64 << shift) - 1));
+            if r > half || (r == half && q & 1 == 1) {
+                q += 1;
+            }
+            if q >> (4 * p) != 0 {
+                q = 0;
+                lead += 1;
+            }
+            if lead == 16 {
+                lead = 1;
+                exp += 4;
+            }
+            match p {
+                0 => String::new(),
+                _ => format!("{q:0p$x}"),
             }
-            return;
         }
+    };
+
+    let point = if alt || !digits.is_empty() { "." } else { "" };
+    let sign = if exp < 0 { '-' } else { '+' };
+    format!("{lead:x}{point}{digits}p{sign}{}", exp.abs())
+}
+
+/* for %q: backslashes, or $'...' if there are control characters */
+fn quote(s: &str) -> String {
+    if s.is_empty() {
+        return "''".to_string();
+    }
+    if s.chars().any(|c| c.is_control()) {
+        return ansi_c_quote(s);
+    }
 
-        while s.len() < len {
-            s.insert(0, padding);
+    let mut ans = String::new();
+    let mut prev = None;
+    for c in s.chars() {
+        let escape = match c {
+            '~' => prev.is_none() || prev == Some('=') || prev == Some(':'),
+            '#' => prev.is_none(),
+            _ => " \t\n'\"\\|&;()<>!{}*[?]^$`,".contains(c),
+        };
+        if escape {
+            ans.push('\\');
         }
+        ans.push(c);
+        prev = Some(c);
     }
+    ans
+}
 
-    fn render_value(&mut self, args: &mut Vec<String>) -> Result<String, ExecError> {
-        match self {
-            Self::DI(fmt) => {
-                let mut a = pop(args);
-                Self::padding(&mut a, fmt.clone(), true);
-                Ok(a)
+fn ansi_c_quote(s: &str) -> String {
+    let mut ans = "$'".to_string();
+    for c in s.chars() {
+        match c {
+            '\x07' => ans += "\\a",
+            '\x08' => ans += "\\b",
+            '\x0c' => ans += "\\f",
+            '\n' => ans += "\\n",
+            '\r' => ans += "\\r",
+            '\t' => ans += "\\t",
+            '\x0b' => ans += "\\v",
+            '\x1b' => ans += "\\E",
+            '\\' | '\'' => {
+                ans.push('\\');
+                ans.push(c);
             }
-            Self::U(fmt) => {
-                let mut a = pop(args);
-                if a.starts_with("-") {
-                    a.remove(0);
-                    let mut num = a.parse::<u64>()?;
-                    num = u64::MAX - num + 1;
-                    let mut a = num.to_string();
-                    Self::padding(&mut a, fmt.clone(), true);
-                    Ok(a)
-                } else {
-                    Self::padding(&mut a, fmt.clone(), true);
-                    Ok(a)
+            _ if c.is_control() => {
+                let mut buf = [0; 4];
+                for b in c.encode_utf8(&mut buf).bytes() {
+                    ans += &format!("\\{b:03o}");
                 }
             }
-            Self::F(fmt) => {
-                let mut a = format!("{:.6}", Self::to_float(&pop(args))?);
-                Self::padding_float(&mut a, fmt.clone());
-                Ok(a)
-            }
-            Self::S(fmt) => {
-                let mut a = pop(args);
-                Self::padding(&mut a, fmt.clone(), false);
-                Ok(a)
+            _ => ans.push(c),
+        }
+    }
+    ans + "'"
+}
+
+fn scan_digits(s: &[u8], radix: u32, max: usize) -> (u32, usize) {
+    let mut n = 0;
+    let mut len = 0;
+    while len < max && len < s.len() {
+        match (s[len] as char).to_digit(radix) {
+            Some(d) => n = n * radix + d,
+            None => break,
+        }
+        len += 1;
+    }
+    (n, len)
+}
+
+struct Escape {
+    bytes: Vec<u8>,
+    len: usize,
+    error: Option<String>,
+    stop: bool,
+}
+
+/* `s' starts after the backslash. `in_format' switches the rules of
+ * the format string and of the arguments of %b */
+fn escape(s: &[u8], in_format: bool) -> Escape {
+    let mut ans = Escape {
+        bytes: vec![],
+        len: 1,
+        error: None,
+        stop: false,
+    };
+
+    let c = match s.first() {
+        Some(c) => *c,
+        None => {
+            ans.bytes = b"\\".to_vec();
+            ans.len = 0;
+            return ans;
+        }
+    };
+
+    ans.bytes = match c {
+        b'a' => vec![7],
+        b'b' => vec![8],
+        b'e' | b'E' => vec![27],
+        b'f' => vec![12],
+        b'n' => vec![b'\n'],
+        b'r' => vec![b'\r'],
+        b't' => vec![b'\t'],
+        b'v' => vec![11],
+        b'\\' => vec![b'\\'],
+        b'"' | b'\'' | b'?' if in_format => vec![c],
+        b'c' if !in_format => {
+            ans.stop = true;
+            vec![]
+        }
+        b'0' if !in_format => {
+            let (n, len) = scan_digits(&s[1..], 8, 3);
+            ans.len += len;
+            vec![n as u8]
+        }
+        b'0'..=b'7' => {
+            let (n, len) = scan_digits(s, 8, 3);
+            ans.len = len;
+            vec![n as u8]
+        }
+        b'x' => match scan_digits(&s[1..], 16, 2) {
+            (_, 0) => {
+                ans.error = Some("missing hex digit for \\x".to_string());
+                b"\\x".to_vec()
             }
-            Self::B(fmt) => {
-                let mut a = replace_escape(&pop(args));
-                Self::padding(&mut a, fmt.clone(), false);
-                Ok(a)
+            (n, len) => {
+                ans.len += len;
+                vec![n as u8]
             }
-            Self::X(fmt, hash) => {
-                let mut a = format!("{:x}", Self::to_int(&pop(args))?);
-                if *hash {
-                    a = "0x".to_owned() + &a;
+        },
+        b'u' | b'U' => {
+            let max = if c == b'u' { 4 } else { 8 };
+            match scan_digits(&s[1..], 16, max) {
+                (_, 0) => {
+                    ans.error = Some(format!("missing unicode digit for \\{}", c as char));
+                    vec![b'\\', c]
                 }
-
-                Self::padding(&mut a, fmt.clone(), true);
-                Ok(a)
-            }
-            Self::O(fmt, hash) => {
-                let mut a = format!("{:o}", Self::to_int(&pop(args))?);
-                if *hash {
-                    a.insert(0, '0');
+                (n, len) => {
+                    ans.len += len;
+                    let ch = char::from_u32(n).unwrap_or(char::REPLACEMENT_CHARACTER);
+                    ch.to_string().into_bytes()
                 }
-
-                Self::padding(&mut a, fmt.clone(), true);
-                Ok(a)
             }
-            Self::LargeX(fmt, hash) => {
-                let mut a = format!("{:X}", Self::to_int(&pop(args))?);
-                if *hash {
-                    a = "0X".to_owned() + &a;
-                }
+        }
+        _ => vec![b'\\', c],
+    };
+    ans
+}
 
-                Self::padding(&mut a, fmt.clone(), true);
-                Ok(a)
-            }
-            Self::Q => {
-                let a = pop(args);
-                let mut q = a
-                    .replace("\\", "\\\\")
-                    .replace("$", "\\$")
-                    .replace("|", "\\|")
-                    .replace("\"", "\\\"")
-                    .replace("'", "\\\'")
-                    .replace("~", "\\~")
-                    .replace("(", "\\(")
-                    .replace(")", "\\)")
-                    .replace("{", "\\{")
-                    .replace("}", "\\}")
-                    .replace("!", "\\!")
-                    .replace("&", "\\&");
-
-                if q == "" {
-                    q = "''".to_string();
-                }
+fn parse_conversion(fmt: &[u8], pos: &mut usize) -> PrintfToken {
+    let start = *pos;
+    *pos += 1; // %
+    let mut spec = Spec::default();
 
-                Ok(q)
-            }
-            Self::Other(s) => {
-                let a = pop(args);
-                let formatted = match sprintf::sprintf!(&s, a) {
-                    Ok(res) => res,
-                    Err(e) => {
-                        let msg = format!("{} {} {}", &e, &s, &a);
-                        return Err(ExecError::Other(msg));
-                    }
-                };
+    while *pos < fmt.len() && b"-+ #0'".contains(&fmt[*pos]) {
+        spec.flags.push(fmt[*pos] as char);
+        *pos += 1;
+    }
 
-                Ok(formatted)
-            }
-            Self::EscapedChar(c) => Ok(esc_to_str(*c)),
-            Self::Normal(s) => Ok(s.clone()),
+    let count = |pos: &mut usize| -> Option<Count> {
+        if *pos < fmt.len() && fmt[*pos] == b'*' {
+            *pos += 1;
+            return Some(Count::Star);
         }
+        let (n, len) = scan_digits(&fmt[*pos..], 10, usize::MAX);
+        *pos += len;
+        (len > 0).then_some(Count::Num(n as usize))
+    };
+
+    spec.width = count(pos);
+    if *pos < fmt.len() && fmt[*pos] == b'.' {
+        *pos += 1;
+        spec.precision = Some(count(pos).unwrap_or(Count::Num(0)));
     }
-}
 
-fn pop(args: &mut Vec<String>) -> String {
-    match args.is_empty() {
-        true => "".to_string(),
-        false => args.remove(0),
+    while *pos < fmt.len() && LENGTH_MODIFIERS.as_bytes().contains(&fmt[*pos]) {
+        *pos += 1;
     }
-}
 
-fn esc_to_str(ch: char) -> String {
-    match ch {
-        'a' => char::from(7).to_string(),
-        'b' => char::from(8).to_string(),
-        'e' | 'E' => char::from(27).to_string(),
-        'f' => char::from(12).to_string(),
-        'n' => "\n".to_string(),
-        'r' => "\r".to_string(),
-        't' => "\t".to_string(),
-        'v' => char::from(11).to_string(),
-        '\\' => "\\".to_string(),
-        '\'' => "'".to_string(),
-        '"' => "\"".to_string(),
-        _ => ("\\".to_owned() + &ch.to_string()).to_string(),
+    if *pos < fmt.len() && fmt[*pos] == b'(' {
+        match fmt[*pos..].iter().position(|c| *c == b')') {
+            Some(len) => {
+                spec.time_fmt = String::from_utf8_lossy(&fmt[*pos + 1..*pos + len]).to_string();
+                *pos += len + 1;
+                if fmt.get(*pos) != Some(&b'T') {
+                    let c = fmt.get(*pos).map(|c| *c as char).unwrap_or('T');
+                    return PrintfToken::Invalid(format!(
+                        "`{c}': invalid time format specification"
+                    ));
+                }
+            }
+            None => {
+                return PrintfToken::Invalid("`(': missing time format specification".to_string())
+            }
+        }
+    }
+
+    let c = match fmt.get(*pos) {
+        Some(c) => *c as char,
+        None => {
+            let text = String::from_utf8_lossy(&fmt[start..]);
+            return PrintfToken::Invalid(format!("`{text}': missing format character"));
+        }
+    };
+    *pos += 1;
+
+    match c {
+        '%' if *pos - start == 2 => PrintfToken::Text(b"%".to_vec()),
+        _ if CONVERSIONS.contains(c) => {
+            spec.conv = c;
+            PrintfToken::Conversion(spec)
+        }
+        _ => PrintfToken::Invalid(format!("`{c}': invalid format character")),
     }
 }
 
-fn replace_escape(s: &str) -> String {
-    let mut ans = String::new();
-    let mut esc = false;
+fn parse(format: &str) -> Vec<PrintfToken> {
+    let fmt = format.as_bytes();
+    let mut ans = vec![];
+    let mut text = vec![];
+    let mut pos = 0;
 
-    for ch in s.chars() {
-        if esc || ch == '\\' {
-            if esc {
-                ans.push_str(&esc_to_str(ch));
+    while pos < fmt.len() {
+        match fmt[pos] {
+            b'\\' => {
+                let esc = escape(&fmt[pos + 1..], true);
+                pos += 1 + esc.len;
+                match esc.error {
+                    Some(msg) => {
+                        ans.push(PrintfToken::Text(std::mem::take(&mut text)));
+                        ans.push(PrintfToken::BadEscape(esc.bytes, msg));
+                    }
+                    None => text.extend(esc.bytes),
+                }
+            }
+            b'%' => {
+                ans.push(PrintfToken::Text(std::mem::take(&mut text)));
+                let token = parse_conversion(fmt, &mut pos);
+                let invalid = matches!(token, PrintfToken::Invalid(_));
+                ans.push(token);
+                if invalid {
+                    return ans;
+                }
+            }
+            c => {
+                text.push(c);
+                pos += 1;
             }
-            esc = !esc;
-            continue;
         }
-
-        ans.push(ch);
     }
 
+    ans.push(PrintfToken::Text(text));
     ans
 }
 
-fn scanner_normal(remaining: &str) -> usize {
-    let mut pos = 0;
+/* like strtoimax(3) with base 0; returns the value and whether all is read */
+fn scan_int(s: &str) -> (i128, bool) {
+    let s = s.trim_start().as_bytes();
+    let (negative, mut pos) = match s.first() {
+        Some(b'-') => (true, 1),
+        Some(b'+') => (false, 1),
+        _ => (false, 0),
+    };
 
-    for c in remaining.chars() {
-        if c == '%' || c == '\\' {
-            break;
+    let radix = match &s[pos..] {
+        [b'0', b'x' | b'X', c, ..] if c.is_ascii_hexdigit() => {
+            pos += 2;
+            16
         }
-        pos += c.len_utf8();
+        [b'0', ..] => 8,
+        _ => 10,
+    };
+
+    let start = pos;
+    let mut n: i128 = 0;
+    while let Some(d) = s.get(pos).and_then(|c| (*c as char).to_digit(radix)) {
+        n = n.saturating_mul(radix as i128).saturating_add(d as i128);
+        pos += 1;
     }
-    pos
+
+    let n = if negative { -n } else { n };
+    (n, pos > start && pos == s.len())
 }
 
-fn scanner_escaped_char(remaining: &str) -> usize {
-    if !remaining.starts_with("\\") {
-        return 0;
+/* like strtold(3); returns the value and whether all is read */
+fn scan_float(s: &str) -> (f64, bool) {
+    let t = s.trim_start();
+    let lower = t.to_ascii_lowercase();
+    let (negative, body) = match lower.strip_prefix('-') {
+        Some(b) => (true, b),
+        None => (false, lower.strip_prefix('+').unwrap_or(&lower)),
+    };
+    /* negation keeps the sign of NaN unlike multiplication */
+    let signed = |v: f64| if negative { -v } else { v };
+
+    for (word, value) in [
+        ("infinity", f64::INFINITY),
+        ("inf", f64::INFINITY),
+        ("nan", f64::NAN),
+    ] {
+        if body.starts_with(word) {
+            return (signed(value), body.len() == word.len());
+        }
     }
 
-    match remaining.chars().nth(1) {
-        Some(ch) => 1 + ch.len_utf8(),
-        _ => 0,
+    let b = body.as_bytes();
+    let digits = |pos: &mut usize, hex: bool| {
+        let start = *pos;
+        while *pos < b.len() && (b[*pos].is_ascii_digit() || (hex && b[*pos].is_ascii_hexdigit())) {
+            *pos += 1;
+        }
+        *pos - start
+    };
+
+    let hex = body.starts_with("0x")
+        && b.get(2)
+            .is_some_and(|c| c.is_ascii_hexdigit() || *c == b'.');
+    let mut pos = if hex { 2 } else { 0 };
+    let mut n = digits(&mut pos, hex);
+    let point = pos;
+    if b.get(pos) == Some(&b'.') {
+        pos += 1;
+        n += digits(&mut pos, hex);
     }
-}
+    if n == 0 {
+        return (0.0, false);
+    }
+    let mantissa_end = pos;
 
-fn scanner_hash(remaining: &str) -> usize {
-    let mut ans = 0;
-    for c in remaining.chars() {
-        if c != '#' {
-            break;
+    let exp_char = if hex { b'p' } else { b'e' };
+    if b.get(pos) == Some(&exp_char) {
+        let mut p = pos + 1;
+        if matches!(b.get(p), Some(b'+' | b'-')) {
+            p += 1;
+        }
+        if digits(&mut p, false) > 0 {
+            pos = p;
         }
-        ans += 1;
     }
-    ans
-}
 
-fn scanner_format_num(remaining: &str) -> usize {
-    let mut ans = 0;
-    for c in remaining.chars() {
-        if !"-.".contains(c) && !c.is_ascii_digit() {
-            break;
+    let value = match hex {
+        false => body[..pos].parse::<f64>().unwrap_or(0.0),
+        true => {
+            let mut m = 0.0;
+            let mut scale = 0;
+            for (i, c) in body[2..mantissa_end].chars().enumerate() {
+                if let Some(d) = c.to_digit(16) {
+                    m = m * 16.0 + d as f64;
+                    if i + 2 > point {
+                        scale -= 4;
+                    }
+                }
+            }
+            let exp = body
+                .get(mantissa_end + 1..pos)
+                .and_then(|e| e.parse::<i32>().ok());
+            m * 2f64.powi(scale + exp.unwrap_or(0))
         }
+    };
+
+    (signed(value), pos == b.len())
+}
 
-        ans += 1;
+/* 'a or "a gives the code of a */
+fn char_value(s: &str) -> Option<i128> {
+    match s.starts_with(['\'', '"']) {
+        true => Some(s.chars().nth(1).map(|c| c as i128).unwrap_or(0)),
+        false => None,
     }
-    ans
 }
 
-fn parse(pattern: &str) -> Vec<PrintfToken> {
-    let mut remaining = pattern.to_string();
-    let mut ans = vec![];
+unsafe extern "C" {
+    fn tzset();
+}
 
-    while !remaining.is_empty() {
-        let len = scanner_normal(&remaining);
-        if len > 0 {
-            let tail = remaining.split_off(len);
-            ans.push(PrintfToken::Normal(remaining));
-            remaining = tail;
-            continue;
-        }
+fn strftime(core: &mut ShellCore, fmt: &str, time: i64) -> Vec<u8> {
+    let fmt = if fmt.is_empty() { "%X" } else { fmt };
+    let c_fmt = match CString::new(fmt) {
+        Ok(f) => f,
+        Err(_) => return vec![],
+    };
 
-        let len = scanner_escaped_char(&remaining);
-        if len > 0 {
-            remaining.remove(0);
-            ans.push(PrintfToken::EscapedChar(remaining.remove(0)));
-            continue;
+    /* TZ is used only when it is exported or inherited */
+    let env_tz = env::var("TZ").ok();
+    let exported = env_tz.is_some() || core.db.has_flag("TZ", 'x');
+    let tz = match exported && core.db.exist("TZ") {
+        true => core.db.get_param("TZ").ok(),
+        false => None,
+    };
+    unsafe {
+        match tz {
+            Some(tz) => env::set_var("TZ", tz),
+            None => env::remove_var("TZ"),
         }
+        tzset();
+    }
 
-        if remaining.starts_with("%") {
-            remaining.remove(0); // %
-
-            let mut has_hash = false;
-            let mut num_part = String::new();
-            let len = scanner_hash(&remaining);
-            if len > 0 {
-                let tail = remaining.split_off(len);
-                has_hash = true;
-                remaining = tail;
-            }
-
-            let len = scanner_format_num(&remaining);
-            if len > 0 {
-                let tail = remaining.split_off(len);
-                num_part = remaining.clone();
-                remaining = tail;
-            }
+    let t = time as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    unsafe { libc::localtime_r(&t, &mut tm) };
 
-            let token = match remaining.chars().next() {
-                Some('b') => PrintfToken::B(num_part),
-                Some('d') => PrintfToken::DI(num_part),
-                Some('i') => PrintfToken::DI(num_part),
-                Some('f') => PrintfToken::F(num_part),
-                Some('o') => PrintfToken::O(num_part, has_hash),
-                Some('s') => PrintfToken::S(num_part),
-                Some('u') => PrintfToken::U(num_part),
-                Some('x') => PrintfToken::X(num_part, has_hash),
-                Some('X') => PrintfToken::LargeX(num_part, has_hash),
-                Some('q') => PrintfToken::Q,
-                Some(c) => PrintfToken::Other("%".to_owned() + &num_part + &c.to_string()),
-                None => PrintfToken::Normal("%".to_string()),
-            };
+    let mut buf = vec![0u8; 128 + fmt.len() * 16];
+    let len = unsafe { libc::strftime(buf.as_mut_ptr() as *mut _, buf.len(), c_fmt.as_ptr(), &tm) };
+    buf.truncate(len);
 
-            remaining.remove(0);
-            ans.push(token);
+    unsafe {
+        match env_tz {
+            Some(tz) => env::set_var("TZ", tz),
+            None => env::remove_var("TZ"),
         }
+        tzset();
     }
+    buf
+}
 
-    ans
+struct Printf {
+    args: Vec<String>,
+    pos: usize,
+    out: Vec<u8>,
+    exit_status: i32,
+    stop: bool,
 }
 
-fn format(pattern: &str, args: &mut Vec<String>) -> Result<String, ExecError> {
-    let mut ans = String::new();
+impl Printf {
+    fn next_arg(&mut self) -> Option<String> {
+        let arg = self.args.get(self.pos)?;
+        self.pos += 1;
+        Some(arg.split('\0').next().unwrap_or("").to_string())
+    }
+
+    fn invalid_number(&mut self, core: &mut ShellCore, arg: &str) {
+        self.exit_status = error_(1, "printf", &format!("{arg}: invalid number"), core);
+    }
 
-    let mut tokens = parse(pattern);
-    let mut fin = true;
+    fn out_of_range(&mut self, core: &mut ShellCore, arg: &str) {
+        let msg = format!("warning: {arg}: Numerical result out of range");
+        error_(1, "printf", &msg, core);
+    }
 
-    for tok in tokens.iter_mut() {
-        if tok.continue_() {
-            fin = false;
+    fn int_arg(&mut self, core: &mut ShellCore, unsigned: bool) -> i128 {
+        let arg = match self.next_arg() {
+            Some(a) if !a.is_empty() => a,
+            _ => return 0,
+        };
+        if let Some(n) = char_value(&arg) {
+            return n;
         }
-        ans += &tok.render_value(args)?;
+
+        let (n, complete) = scan_int(&arg);
+        if !complete {
+            self.invalid_number(core, &arg);
+        }
+
+        let (min, max) = match unsigned {
+            true => (-(u64::MAX as i128), u64::MAX as i128),
+            false => (i64::MIN as i128, i64::MAX as i128),
+        };
+        if n < min || n > max {
+            self.out_of_range(core, &arg);
+            return match (unsigned, n < min) {
+                (true, _) => max,
+                (false, true) => min,
+                (false, false) => max,
+            };
+        }
+        n
     }
 
-    if !args.is_empty() && !fin {
-        if let Ok(s) = format(pattern, args) {
-            ans += &s;
+    fn float_arg(&mut self, core: &mut ShellCore) -> f64 {
+        let arg = match self.next_arg() {
+            Some(a) if !a.is_empty() => a,
+            _ => return 0.0,
+        };
+        if let Some(n) = char_value(&arg) {
+            return n as f64;
+        }
+
+        let (f, complete) = scan_float(&arg);
+        if !complete {
+            self.invalid_number(core, &arg);
         }
+        f
     }
-    Ok(ans)
-}
 
-fn arg_check(core: &mut ShellCore, args: &[String]) -> i32 {
-    if args.len() < 2 || args[1] == "--help" || args[1] == "-v" && args.len() == 3 {
-        let msg = "printf: usage: printf [-v var] format [arguments]".to_string();
-        error::print(&msg, core);
-        return 2;
+    fn field(&mut self, core: &mut ShellCore, spec: &Spec) -> Field {
+        let mut left = spec.flags.contains('-');
+        let width = match spec.width {
+            Some(Count::Star) => {
+                let w = self.int_arg(core, false) as i64;
+                left |= w < 0;
+                w.unsigned_abs() as usize
+            }
+            Some(Count::Num(n)) => n,
+            None => 0,
+        };
+        let precision = match spec.precision {
+            Some(Count::Star) => usize::try_from(self.int_arg(core, false)).ok(),
+            Some(Count::Num(n)) => Some(n),
+            None => None,
+        };
+
+        Field {
+            left,
+            zero: spec.flags.contains('0'),
+            plus: spec.flags.contains('+'),
+            space: spec.flags.contains(' '),
+            alt: spec.flags.contains('#'),
+            width,
+            precision,
+            conv: spec.conv,
+        }
     }
 
-    if args[1] == "-v" && args.len() == 2 {
-        let msg = "printf: -v: option requires an argument".to_string();
-        error::print(&msg, core);
-        let msg = "printf: usage: printf [-v var] format [arguments]".to_string();
-        error::print(&msg, core);
-        return 2;
+    fn expand_b(&mut self, arg: &str) -> Vec<u8> {
+        let s = arg.as_bytes();
+        let mut ans = vec![];
+        let mut pos = 0;
+        while pos < s.len() {
+            if s[pos] != b'\\' {
+                ans.push(s[pos]);
+                pos += 1;
+                continue;
+            }
+
+            let esc = escape(&s[pos + 1..], false);
+            ans.extend(esc.bytes);
+            if esc.stop {
+                self.stop = true;
+                break;
+            }
+            pos += 1 + esc.len;
+        }
+        ans
     }
 
-    0
-}
+    fn render(&mut self, core: &mut ShellCore, spec: &Spec) {
+        let field = self.field(core, spec);
+        let body = match spec.conv {
+            'd' | 'i' => field.signed(self.int_arg(core, false) as i64),
+            'o' | 'u' | 'x' | 'X' => field.unsigned(self.int_arg(core, true) as u64),
+            'c' => {
+                let c = self.next_arg().and_then(|a| a.bytes().next()).unwrap_or(0);
+                field.pad(vec![c])
+            }
+            's' => {
+                let s = self.next_arg().unwrap_or_default().into_bytes();
+                field.pad(field.truncate(s))
+            }
+            'b' => {
+                let arg = self.next_arg().unwrap_or_default();
+                let s = self.expand_b(&arg);
+                field.pad(field.truncate(s))
+            }
+            'q' => {
+                let s = quote(&self.next_arg().unwrap_or_default()).into_bytes();
+                field.pad(field.truncate(s))
+            }
+            'Q' => {
+                let arg = field.truncate(self.next_arg().unwrap_or_default().into_bytes());
+                field.pad(quote(&String::from_utf8_lossy(&arg)).into_bytes())
+            }
+            'T' => {
+                let time = match self.args.get(self.pos) {
+                    Some(_) => self.int_arg(core, false) as i64,
+                    None => -1,
+                };
+                let time = match time {
+                    -1 => clock::epoch_seconds(),
+                    -2 => core.start_time,
+                    t => t,
+                };
+                let s = strftime(core, &spec.time_fmt, time);
+                field.pad(field.truncate(s))
+            }
+            _ => field.float(self.float_arg(core)),
+        };
+        self.out.extend(body);
+    }
 
-fn printf_v(core: &mut ShellCore, args: &mut Vec<String>) -> i32 {
-    if args[3] == "--" {
-        args.remove(3);
+    fn write_out(&mut self, core: &mut ShellCore) -> bool {
+        let mut stdout = stdout();
+        if let Err(e) = stdout.write_all(&self.out).and_then(|_| stdout.flush()) {
+            let errno = Errno::from_raw(e.raw_os_error().unwrap_or(0));
+            let msg = format!("write error: {}", errno.desc());
+            self.exit_status = error_(1, "printf", &msg, core);
+            return false;
+        }
+        self.out.clear();
+        true
     }
 
-    let s = match format(&args[3], &mut args[4..].to_vec()) {
-        Ok(ans) => ans,
-        Err(e) => {
-            let msg = String::from(&e);
-            return super::error_(1, "printf", &msg, core);
+    /* returns false if the output must stop */
+    fn run(&mut self, core: &mut ShellCore, tokens: &[PrintfToken]) -> bool {
+        for token in tokens {
+            match token {
+                PrintfToken::Text(s) => self.out.extend(s),
+                PrintfToken::BadEscape(s, msg) => {
+                    error_(1, "printf", msg, core);
+                    self.out.extend(s);
+                }
+                PrintfToken::Conversion(spec) => self.render(core, spec),
+                PrintfToken::Invalid(msg) => {
+                    self.exit_status = error_(1, "printf", msg, core);
+                    return false;
+                }
+            }
+
+            if self.stop {
+                return false;
+            }
         }
+        true
+    }
+}
+
+/* The format is reused while it consumes the arguments. The output
+ * is written on each round unless it goes to a variable. */
+fn format(core: &mut ShellCore, format: &str, args: &[String], to_stdout: bool) -> (Vec<u8>, i32) {
+    let tokens = parse(format);
+    let mut printf = Printf {
+        args: args.to_vec(),
+        pos: 0,
+        out: vec![],
+        exit_status: 0,
+        stop: false,
     };
 
-    if args[2].contains("[") {
-        let mut f = Feeder::new(&(args[2].clone() + "=" + &s));
-        if let Ok(Some(mut a)) = Substitution::parse(&mut f, core, false, false) {
-            if let Err(e) = a.eval(core, None, false) {
-                let msg = String::from(&e);
-                return super::error_(2, "printf", &msg, core);
-            }
-        } else {
-            return 1;
+    loop {
+        let pos = printf.pos;
+        let cont = printf.run(core, &tokens);
+        if to_stdout && !printf.write_out(core) {
+            break;
+        }
+        if !cont || printf.pos >= args.len() || printf.pos == pos {
+            break;
         }
-        return 0;
     }
-    if let Err(e) = core.db.set_param(&args[2], &s, None) {
-        let msg = String::from(&e);
-        return super::error_(2, "printf", &msg, core);
+    (printf.out, printf.exit_status)
+}
+
+fn assign(core: &mut ShellCore, name: &str, value: &[u8]) -> i32 {
+    let value = value.split(|c| *c == 0).next().unwrap_or(&[]);
+    let value = String::from_utf8_lossy(value).to_string();
+
+    if name.contains('[') {
+        let mut f = Feeder::new(&(name.to_string() + "=" + &value));
+        return match Substitution::parse(&mut f, core, false, false) {
+            Ok(Some(mut a)) => match a.eval(core, None, false) {
+                Ok(_) => 0,
+                Err(e) => error_(2, "printf", &String::from(&e), core),
+            },
+            _ => 1,
+        };
+    }
+
+    match core.db.set_param(name, &value, None) {
+        Ok(_) => 0,
+        Err(e) => error_(2, "printf", &String::from(&e), core),
     }
+}
 
-    0
+fn usage_error(core: &mut ShellCore, msg: &str) -> i32 {
+    if !msg.is_empty() {
+        error_(2, "printf", msg, core);
+    }
+    eprintln!("{USAGE}");
+    2
 }
 
 pub fn printf(core: &mut ShellCore, args: &[String]) -> i32 {
-    let mut args = args.to_owned();
-    match arg_check(core, &args) {
-        0 => {}
-        n => return n,
+    let mut var = None;
+    let mut i = 1;
+
+    while i < args.len() && args[i].starts_with('-') && args[i] != "-" {
+        match args[i].as_str() {
+            "--" => {
+                i += 1;
+                break;
+            }
+            "--help" => return usage_error(core, ""),
+            "-v" if i + 1 < args.len() => {
+                var = Some(args[i + 1].clone());
+                i += 1;
+            }
+            "-v" => return usage_error(core, "-v: option requires an argument"),
+            a if a.starts_with("-v") => var = Some(a[2..].to_string()),
+            a => return usage_error(core, &format!("{}: invalid option", &a[..2])),
+        }
+        i += 1;
     }
 
-    if args[1] == "-v" {
-        return printf_v(core, &mut args);
+    if i >= args.len() {
+        return usage_error(core, "");
     }
 
-    let s = match format(&args[1], &mut args[2..].to_vec()) {
-        Ok(ans) => ans,
-        Err(e) => {
-            let msg = format!("printf: {e:?}");
-            error::print(&msg, core);
-            return 1;
-        }
-    };
-    print!("{}", &s);
-    stdout().flush().unwrap();
-    0
+    let (out, exit_status) = format(core, &args[i], &args[i + 1..], var.is_none());
+    if let Some(name) = var {
+        let es = assign(core, &name, &out);
+        return if es != 0 { es } else { exit_status };
+    }
+    exit_status
 }
@@ -3,167 +3,508 @@
 
 use super::error_;
 use crate::elements::substitution::variable::Variable;
-use crate::{arg, error, utils, ShellCore};
+use crate::error::input::InputError;
+use crate::feeder::terminal;
+use crate::utils::file_check;
+use crate::{error, signal, ShellCore};
+use nix::errno::Errno;
+use nix::sys::termios;
+use nix::sys::termios::{LocalFlags, SetArg, SpecialCharacterIndices, Termios};
+use nix::unistd;
+use std::io::Write;
+use std::os::fd::{BorrowedFd, RawFd};
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "read: usage: read [-ers] [-a array] [-d delim] [-i text] [-n nchars] [-N nchars] [-p prompt] [-t timeout] [-u fd] [name ...]";
+
+/* 128 + SIGALRM as bash */
+const TIMEOUT_STATUS: i32 = 142;
+
+struct ReadOptions {
+    raw: bool,
+    silent: bool,
+    edit: bool,
+    array: Option<String>,
+    delim: u8,
+    text: String,
+    nchars: Option<usize>,
+    exact: bool,
+    prompt: Option<String>,
+    timeout: Option<Duration>,
+    fd: RawFd,
+}
 
-fn check_word_limit(word: &mut String, limit: &mut usize) -> bool {
-    let mut pos = 0;
-    for c in word.chars() {
-        if *limit == 0 {
-            let _ = word.split_off(pos);
-            return true;
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            raw: false,
+            silent: false,
+            edit: false,
+            array: None,
+            delim: b'\n',
+            text: String::new(),
+            nchars: None,
+            exact: false,
+            prompt: None,
+            timeout: None,
+            fd: 0,
         }
-        *limit -= 1;
+    }
+}
 
-        pos += c.len_utf8();
+fn borrow(fd: RawFd) -> BorrowedFd<'static> {
+    unsafe { BorrowedFd::borrow_raw(fd) }
+}
+
+fn usage_error(core: &mut ShellCore, msg: &str) -> i32 {
+    error_(2, "read", msg, core);
+    eprintln!("{USAGE}");
+    2
+}
+
+fn parse_timeout(s: &str) -> Option<Duration> {
+    if !s.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    s.parse::<f64>().ok().map(Duration::from_secs_f64)
+}
+
+fn set_option(core: &mut ShellCore, opts: &mut ReadOptions, opt: char, value: &str) -> Result<(), i32> {
+    match opt {
+        'a' => opts.array = Some(value.to_string()),
+        'd' => opts.delim = value.bytes().next().unwrap_or(0),
+        'i' => opts.text = value.to_string(),
+        'p' => opts.prompt = Some(value.to_string()),
+        'n' | 'N' => match value.parse::<usize>() {
+            Ok(n) => {
+                opts.nchars = Some(n);
+                opts.exact = opt == 'N';
+            }
+            Err(_) => {
+                let msg = format!("{value}: invalid number");
+                return Err(error_(1, "read", &msg, core));
+            }
+        },
+        't' => match parse_timeout(value) {
+            Some(t) => opts.timeout = Some(t),
+            None => {
+                let msg = format!("{value}: invalid timeout specification");
+                return Err(error_(1, "read", &msg, core));
+            }
+        },
+        _ => {
+            let fd = match value.parse::<RawFd>() {
+                Ok(fd) if fd >= 0 => fd,
+                _ => {
+                    let msg = format!("{value}: invalid file descriptor specification");
+                    return Err(error_(1, "read", &msg, core));
+                }
+            };
+            if nix::fcntl::fcntl(borrow(fd), nix::fcntl::FcntlArg::F_GETFD).is_err() {
+                let msg = format!("{value}: invalid file descriptor: Bad file descriptor");
+                return Err(error_(1, "read", &msg, core));
+            }
+            opts.fd = fd;
+        }
     }
-    false
+    Ok(())
 }
 
-pub fn read_(
-    core: &mut ShellCore,
-    args: &mut Vec<String>,
-    ignore_escape: bool,
-    limit: &mut usize,
-    delim: &String,
-) -> i32 {
-    let mut remaining = utils::read_line_stdin_unbuffered(delim).unwrap_or("".to_string());
-    if remaining.is_empty() {
-        return 1;
+fn parse_options(core: &mut ShellCore, args: &[String]) -> Result<(ReadOptions, Vec<String>), i32> {
+    let mut opts = ReadOptions::default();
+    let mut i = 1;
+
+    while i < args.len() {
+        let arg = &args[i];
+        if arg == "--" {
+            i += 1;
+            break;
+        }
+        if !arg.starts_with('-') || arg == "-" {
+            break;
+        }
+
+        for (pos, c) in arg.char_indices().skip(1) {
+            match c {
+                'r' => opts.raw = true,
+                's' => opts.silent = true,
+                'e' => opts.edit = true,
+                c if "adinNptu".contains(c) => {
+                    let value = match &arg[pos + 1..] {
+                        "" if i + 1 < args.len() => {
+                            i += 1;
+                            args[i].clone()
+                        }
+                        "" => {
+                            let msg = format!("-{c}: option requires an argument");
+                            return Err(usage_error(core, &msg));
+                        }
+                        rest => rest.to_string(),
+                    };
+                    set_option(core, &mut opts, c, &value)?;
+                    break;
+                }
+                c => return Err(usage_error(core, &format!("-{c}: invalid option"))),
+            }
+        }
+        i += 1;
+    }
+
+    Ok((opts, args[i..].to_vec()))
+}
+
+/* gives Err with the exit status on a timeout or an interrupt */
+fn read_byte(core: &mut ShellCore, fd: RawFd, deadline: Option<Instant>, wait: bool) -> Result<Option<u8>, i32> {
+    let mut ch = [0u8; 1];
+    loop {
+        if wait {
+            let timeout = match deadline {
+                Some(d) => match d.saturating_duration_since(Instant::now()) {
+                    rest if rest.is_zero() => return Err(TIMEOUT_STATUS),
+                    rest => rest.as_millis().min(i32::MAX as u128) as i32,
+                },
+                None => -1,
+            };
+
+            if !signal::wait_event_timeout(Some(fd), timeout) {
+                if core.sigint.load(Relaxed) {
+                    return Err(130);
+                }
+                continue;
+            }
+        }
+
+        match unistd::read(borrow(fd), &mut ch) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(ch[0])),
+            Err(Errno::EINTR) if !core.sigint.load(Relaxed) => continue,
+            Err(Errno::EINTR) => return Err(130),
+            Err(_) => return Ok(None),
+        }
+    }
+}
+
+fn read_char(core: &mut ShellCore, fd: RawFd, deadline: Option<Instant>, wait: bool) -> Result<Option<Vec<u8>>, i32> {
+    let first = match read_byte(core, fd, deadline, wait)? {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+
+    let len = match first {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => 1,
+    };
+
+    let mut ans = vec![first];
+    while ans.len() < len {
+        match read_byte(core, fd, deadline, wait)? {
+            Some(b) => ans.push(b),
+            None => break,
+        }
+    }
+    Ok(Some(ans))
+}
+
+/* returns the input without the delimiter and the exit status.
+ * Backslashes are left for word splitting except those for line continuation. */
+fn read_line(core: &mut ShellCore, opts: &ReadOptions, wait: bool) -> (String, i32) {
+    let deadline = opts.timeout.map(|t| Instant::now() + t);
+    let limit = opts.nchars.unwrap_or(usize::MAX);
+    let mut line = vec![];
+    let mut count = 0;
+    let mut escaped = false;
+
+    let exit_status = loop {
+        if count >= limit {
+            break 0;
+        }
+
+        let ch = match read_char(core, opts.fd, deadline, wait) {
+            Ok(Some(ch)) => ch,
+            Ok(None) => break 1,
+            Err(exit_status) => break exit_status,
+        };
+
+        if escaped {
+            escaped = false;
+            if ch == [b'\n'] {
+                line.pop();
+                continue;
+            }
+        } else if ch == [b'\\'] && !opts.raw {
+            escaped = true;
+            line.push(b'\\');
+            continue;
+        } else if ch == [opts.delim] && !opts.exact {
+            break 0;
+        }
+
+        line.extend(ch);
+        count += 1;
+    };
+
+    (String::from_utf8_lossy(&line).to_string(), exit_status)
+}
+
+fn edit_line(core: &mut ShellCore, opts: &ReadOptions) -> (String, i32) {
+    let prompt = opts.prompt.clone().unwrap_or_default();
+    match terminal::read_line_with_text(core, &prompt, &opts.text) {
+        Ok(mut line) => {
+            if line.ends_with('\n') {
+                line.pop();
+            }
+            (line, 0)
+        }
+        Err(InputError::Interrupt) => (String::new(), 130),
+        Err(_) => (String::new(), 1),
+    }
+}
+
+/* -s and -n/-N switch off echo and line buffering of the terminal */
+fn setup_terminal(opts: &ReadOptions) -> Option<Termios> {
+    if !opts.silent && opts.nchars.is_none() {
+        return None;
+    }
+
+    let orig = termios::tcgetattr(borrow(opts.fd)).ok()?;
+    let mut term = orig.clone();
+    if opts.silent {
+        term.local_flags.remove(LocalFlags::ECHO);
+    }
+    if opts.nchars.is_some() {
+        term.local_flags.remove(LocalFlags::ICANON);
+        term.control_chars[SpecialCharacterIndices::VMIN as usize] = 1;
+        term.control_chars[SpecialCharacterIndices::VTIME as usize] = 0;
     }
 
-    let ifs = match core.db.exist("IFS") {
+    termios::tcsetattr(borrow(opts.fd), SetArg::TCSADRAIN, &term).ok()?;
+    Some(orig)
+}
+
+fn read_input(core: &mut ShellCore, opts: &ReadOptions) -> (String, i32) {
+    let tty = file_check::is_tty(opts.fd);
+    if opts.edit && opts.fd == 0 && tty && file_check::is_tty(2) {
+        return edit_line(core, opts);
+    }
+
+    if let Some(prompt) = opts.prompt.as_ref().filter(|_| tty) {
+        eprint!("{prompt}");
+        let _ = std::io::stderr().flush();
+    }
+
+    let saved = match tty {
+        true => setup_terminal(opts),
+        false => None,
+    };
+
+    let ans = read_line(core, opts, tty || opts.timeout.is_some());
+
+    if let Some(orig) = saved {
+        let _ = termios::tcsetattr(borrow(opts.fd), SetArg::TCSADRAIN, &orig);
+    }
+    ans
+}
+
+fn unescape(s: &str, ignore_escape: bool) -> String {
+    if ignore_escape {
+        return s.to_string();
+    }
+
+    let mut esc = false;
+    let mut ans = String::new();
+    for c in s.chars() {
+        if c == '\\' && !esc {
+            esc = true;
+            continue;
+        }
+        esc = false;
+        ans.push(c);
+    }
+    ans
+}
+
+fn get_ifs(core: &mut ShellCore) -> String {
+    match core.db.exist("IFS") {
         true => core.db.get_param("IFS").unwrap(),
         false => " \t\n".to_string(),
-    };
+    }
+}
+
+fn set_var(core: &mut ShellCore, name: &str, value: &str) -> Result<(), i32> {
+    match Variable::parse_and_set(name, value, core) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(super::error_(1, "read", &String::from(&e), core)),
+    }
+}
 
-    let mut tail_space = ifs
+pub fn read_(core: &mut ShellCore, names: &mut Vec<String>, line: &str, ignore_escape: bool) -> i32 {
+    if names.is_empty() {
+        return match set_var(core, "REPLY", &unescape(line, ignore_escape)) {
+            Ok(_) => 0,
+            Err(exit_status) => exit_status,
+        };
+    }
+
+    let mut remaining = line.to_string();
+    let ifs = get_ifs(core);
+    let tail_space = ifs
         .chars()
         .filter(|i| " \t\n".contains(*i))
         .collect::<String>();
-    tail_space += delim;
-
-    args.remove(0);
-    if args.is_empty() {
-        args.push("REPLY".to_string());
-    }
 
-    consume_ifs(&mut remaining, " \t", limit);
+    consume_ifs(&mut remaining, " \t");
 
-    while !args.is_empty() && !remaining.is_empty() && *limit != 0 {
-        let mut word = match eat_word(core, &mut remaining, &ifs, ignore_escape, delim) {
+    while !names.is_empty() && !remaining.is_empty() {
+        let mut word = match eat_word(&mut remaining, &ifs, ignore_escape) {
             Some(w) => w,
             None => break,
         };
 
-        check_word_limit(&mut word, limit);
-
-        if args.len() == 1 && *limit != 0 {
+        if names.len() == 1 {
             let bkup = remaining.clone();
-            consume_ifs(&mut remaining, &ifs, limit);
+            consume_ifs(&mut remaining, &ifs);
 
-            if remaining.is_empty() || remaining == "\n" {
-            } else {
-                word += &bkup;
+            if !remaining.is_empty() && remaining != "\n" {
+                word += &unescape(&bkup, ignore_escape);
             }
         }
 
         consume_tail_ifs(&mut word, &tail_space);
 
-        if let Err(e) = Variable::parse_and_set(&args[0], &word, core) {
-            return super::error_(1, "read", &String::from(&e), core);
+        if let Err(exit_status) = set_var(core, &names[0], &word) {
+            return exit_status;
         }
 
-        args.remove(0);
-        consume_ifs(&mut remaining, &ifs, limit);
+        names.remove(0);
+        consume_ifs(&mut remaining, &ifs);
+    }
+
+    for name in names.iter() {
+        if let Err(exit_status) = set_var(core, name, "") {
+            return exit_status;
+        }
     }
 
     0
 }
 
-pub fn read_a(
-    core: &mut ShellCore,
-    name: &str,
-    ignore_escape: bool,
-    limit: &mut usize,
-    delim: &String,
-) -> i32 {
-    let mut remaining = utils::read_line_stdin_unbuffered(delim).unwrap_or("".to_string());
-    if remaining.is_empty() {
-        return 1;
+fn init_array(core: &mut ShellCore, name: &str) -> Result<(), i32> {
+    let i_flag = core.db.has_flag(name, 'i');
+    match core.db.init_array(name, Some(vec![]), None, i_flag) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            e.print(core);
+            Err(1)
+        }
     }
+}
 
-    let ifs = match core.db.exist("IFS") {
-        true => core.db.get_param("IFS").unwrap(),
-        false => " \t\n".to_string(),
-    };
+fn set_array_elem(core: &mut ShellCore, name: &str, word: &str, pos: isize) -> Result<(), i32> {
+    match core.db.set_array_elem(name, word, pos, None, false) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let msg = format!("{:?}", &e);
+            error::print(&msg, core);
+            Err(1)
+        }
+    }
+}
+
+pub fn read_a(core: &mut ShellCore, name: &str, line: &str, ignore_escape: bool) -> i32 {
+    if let Err(exit_status) = init_array(core, name) {
+        return exit_status;
+    }
 
-    let mut tail_space = ifs
+    let mut remaining = line.to_string();
+    let ifs = get_ifs(core);
+    let tail_space = ifs
         .chars()
         .filter(|i| " \t\n".contains(*i))
         .collect::<String>();
-    tail_space += delim;
 
-    consume_ifs(&mut remaining, " \t", limit);
+    consume_ifs(&mut remaining, " \t");
 
     let mut pos = 0;
     while !remaining.is_empty() {
-        let mut word = match eat_word(core, &mut remaining, &ifs, ignore_escape, delim) {
+        let mut word = match eat_word(&mut remaining, &ifs, ignore_escape) {
             Some(w) => w,
             None => break,
         };
-        check_word_limit(&mut word, limit);
         consume_tail_ifs(&mut word, &tail_space);
 
-        if let Err(e) = core.db.set_array_elem(name, &word, pos, None, false) {
-            let msg = format!("{:?}", &e);
-            error::print(&msg, core);
-            return 1;
+        if let Err(exit_status) = set_array_elem(core, name, &word, pos) {
+            return exit_status;
         }
         pos += 1;
-        consume_ifs(&mut remaining, &ifs, limit);
+        consume_ifs(&mut remaining, &ifs);
     }
 
     0
 }
 
+/* for -N: the input is not split */
+fn read_n(core: &mut ShellCore, opts: &ReadOptions, names: &[String], line: &str) -> i32 {
+    let value = unescape(line, opts.raw);
+    let result = match &opts.array {
+        Some(name) => init_array(core, name).and_then(|_| set_array_elem(core, name, &value, 0)),
+        None => match names.split_first() {
+            Some((first, rest)) => set_var(core, first, &value)
+                .and_then(|_| rest.iter().try_for_each(|name| set_var(core, name, ""))),
+            None => set_var(core, "REPLY", &value),
+        },
+    };
+
+    match result {
+        Ok(_) => 0,
+        Err(exit_status) => exit_status,
+    }
+}
+
 pub fn read(core: &mut ShellCore, args: &[String]) -> i32 {
     if args.is_empty() {
         return 0;
     }
 
-    let mut args = arg::dissolve_options(args);
-    let r_opt = arg::consume_arg("-r", &mut args);
-    let mut limit = usize::MAX;
-    let limit_str = arg::consume_with_next_arg("-n", &mut args);
-    let delim = match arg::consume_with_next_arg("-d", &mut args) {
-        Some(c) => c,
-        None => "\n".to_string(),
+    let (opts, mut names) = match parse_options(core, args) {
+        Ok(ans) => ans,
+        Err(exit_status) => return exit_status,
     };
 
-    if let Some(limit_str) = limit_str {
-        match limit_str.parse::<usize>() {
-            Ok(n) => limit = n,
-            Err(_) => {
-                let err = format!("{}: invalid number", &limit_str);
-                return error_(1, "read", &err, core);
-            }
+    /* only checks if input is available */
+    if opts.timeout.is_some_and(|t| t.is_zero()) {
+        return match signal::wait_event_timeout(Some(opts.fd), 0) {
+            true => 0,
+            false => 1,
         };
     }
 
-    if let Some(a) = arg::consume_with_next_arg("-a", &mut args) {
-        return read_a(core, &a, r_opt, &mut limit, &delim);
+    if opts.nchars == Some(0) {
+        return 0;
+    }
+
+    let (line, exit_status) = read_input(core, &opts);
+    if exit_status == 130 {
+        return exit_status;
     }
 
-    read_(core, &mut args, r_opt, &mut limit, &delim)
+    let set_status = if opts.exact {
+        read_n(core, &opts, &names, &line)
+    } else if let Some(a) = &opts.array {
+        read_a(core, a, &line, opts.raw)
+    } else {
+        read_(core, &mut names, &line, opts.raw)
+    };
+
+    match set_status {
+        0 => exit_status,
+        _ => set_status,
+    }
 }
 
-pub fn eat_word(
-    _core: &mut ShellCore,
-    remaining: &mut String,
-    ifs: &str,
-    ignore_escape: bool,
-    delim: &String,
-) -> Option<String> {
+pub fn eat_word(remaining: &mut String, ifs: &str, ignore_escape: bool) -> Option<String> {
     let mut esc = false;
     let mut pos = 0;
     let mut escape_pos = vec![];
@@ -184,24 +525,11 @@ pub fn eat_word(
         pos += c.len_utf8();
     }
 
-    if let Some(p) = escape_pos.last() {
-        if p + 2 == remaining.len() && remaining.ends_with('\n') {
-            remaining.pop();
-            remaining.pop();
-
-            let line = utils::read_line_stdin_unbuffered(delim).unwrap_or("".to_string());
-            if !line.is_empty() {
-                *remaining += &line;
-                return eat_word(_core, remaining, ifs, ignore_escape, delim);
-            }
-        }
-    }
-
     let tail = remaining.split_off(pos);
     let mut ans = remaining.clone();
     *remaining = tail;
 
-    for p in escape_pos {
+    for p in escape_pos.into_iter().rev() {
         ans.remove(p);
     }
 
@@ -220,13 +548,13 @@ pub fn consume_tail_ifs(remaining: &mut String, ifs: &str) {
     }
 }
 
-pub fn consume_ifs(remaining: &mut String, ifs: &str, limit: &mut usize) {
+pub fn consume_ifs(remaining: &mut String, ifs: &str) {
     let special_ifs: Vec<char> = ifs.chars().filter(|s| !" \t\n".contains(*s)).collect();
     let mut pos = 0;
     let mut special_ifs_exist = false;
 
     for ch in remaining.chars() {
-        if !ifs.contains(ch) || *limit == 0 {
+        if !ifs.contains(ch) {
             break;
         }
 
@@ -238,7 +566,6 @@ pub fn consume_ifs(remaining: &mut String, ifs: &str, limit: &mut usize) {
             special_ifs_exist = true;
         }
         pos += ch.len_utf8();
-        *limit -= 1;
     }
 
     let tail = remaining.split_off(pos);
@@ -2,7 +2,7 @@
 //SPDX-License-Identifier: BSD-3-Clause
 
 use crate::error::parse::ParseError;
-use crate::{file_check, Feeder, Script, ShellCore};
+use crate::{file_check, signal, Feeder, Script, ShellCore};
 
 fn check_error(core: &mut ShellCore, args: &[String]) -> i32 {
     if core.db.flags.contains('r') && args[1].contains('/') {
@@ -65,6 +65,9 @@ pub fn source(core: &mut ShellCore, args: &[String]) -> i32 {
         }
     }
 
+    core.return_flag = false;
+    signal::run_return_trap(core);
+
     source.remove(0);
     let _ = core.db.init_array("BASH_SOURCE", Some(source), None, false);
     core.db.position_parameters.pop();
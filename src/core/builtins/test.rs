@@ -0,0 +1,194 @@
+//SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
+//SPDX-License-Identifier: BSD-3-Clause
+
+use super::error_;
+use crate::elements::expr::conditional::ConditionalExpr;
+use crate::utils::file_check;
+use crate::ShellCore;
+
+const UNARY_OPS: [&str; 26] = [
+    "-a", "-b", "-c", "-d", "-e", "-f", "-g", "-h", "-k", "-n", "-p", "-r", "-s", "-t", "-u",
+    "-w", "-x", "-z", "-G", "-L", "-N", "-O", "-S", "-o", "-v", "-R",
+];
+
+const BINARY_OPS: [&str; 14] = [
+    "=", "==", "!=", "<", ">", "-nt", "-ot", "-ef", "-eq", "-ne", "-lt", "-le", "-gt", "-ge",
+];
+
+fn is_unary(s: &str) -> bool {
+    UNARY_OPS.contains(&s)
+}
+
+fn is_binary(s: &str) -> bool {
+    BINARY_OPS.contains(&s)
+}
+
+fn to_int(s: &str) -> Result<i64, String> {
+    match s.trim().parse::<i64>() {
+        Ok(n) => Ok(n),
+        Err(_) => Err(format!("{s}: integer expression expected")),
+    }
+}
+
+fn unary(core: &mut ShellCore, op: &str, operand: &str) -> Result<bool, String> {
+    ConditionalExpr::unary_test(op, operand, core).map_err(|e| String::from(&e))
+}
+
+fn binary(left: &str, op: &str, right: &str) -> Result<bool, String> {
+    let ans = match op {
+        "=" | "==" => left == right,
+        "!=" => left != right,
+        "<" => left < right,
+        ">" => left > right,
+        "-nt" | "-ot" | "-ef" => file_check::metadata_comp(left, right, op),
+        _ => {
+            let (lnum, rnum) = (to_int(left)?, to_int(right)?);
+            match op {
+                "-eq" => lnum == rnum,
+                "-ne" => lnum != rnum,
+                "-lt" => lnum < rnum,
+                "-le" => lnum <= rnum,
+                "-gt" => lnum > rnum,
+                _ => lnum >= rnum,
+            }
+        }
+    };
+    Ok(ans)
+}
+
+/* recursive descent parser used when the POSIX rules for
+ * up to four arguments don't decide the meaning */
+struct Parser<'a> {
+    args: &'a [String],
+    argc: usize, /* `]' of `[' is left in args for error messages */
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn or(&mut self, core: &mut ShellCore) -> Result<bool, String> {
+        let mut ans = self.and(core)?;
+        while self.pos < self.argc && self.args[self.pos] == "-o" {
+            self.pos += 1;
+            let right = self.and(core)?;
+            ans = ans || right;
+        }
+        Ok(ans)
+    }
+
+    fn and(&mut self, core: &mut ShellCore) -> Result<bool, String> {
+        let mut ans = self.term(core)?;
+        while self.pos < self.argc && self.args[self.pos] == "-a" {
+            self.pos += 1;
+            let right = self.term(core)?;
+            ans = ans && right;
+        }
+        Ok(ans)
+    }
+
+    fn term(&mut self, core: &mut ShellCore) -> Result<bool, String> {
+        if self.pos >= self.argc {
+            return Err("argument expected".to_string());
+        }
+
+        let arg = self.args[self.pos].as_str();
+        if arg == "!" {
+            self.pos += 1;
+            return Ok(!self.term(core)?);
+        }
+
+        if arg == "(" {
+            self.pos += 1;
+            let ans = self.or(core)?;
+            return match self.args.get(self.pos).map(|s| s.as_str()) {
+                Some(")") if self.pos < self.argc => {
+                    self.pos += 1;
+                    Ok(ans)
+                }
+                Some(s) => Err(format!("`)' expected, found {s}")),
+                None => Err("`)' expected".to_string()),
+            };
+        }
+
+        if self.pos + 3 <= self.argc && is_binary(&self.args[self.pos + 1]) {
+            let ans = binary(arg, &self.args[self.pos + 1], &self.args[self.pos + 2]);
+            self.pos += 3;
+            return ans;
+        }
+
+        if self.pos + 2 <= self.argc && is_unary(arg) {
+            let ans = unary(core, arg, &self.args[self.pos + 1]);
+            self.pos += 2;
+            return ans;
+        }
+
+        self.pos += 1;
+        Ok(!arg.is_empty())
+    }
+}
+
+fn full_parse(core: &mut ShellCore, args: &[String], argc: usize) -> Result<bool, String> {
+    let mut parser = Parser { args, argc, pos: 0 };
+    let ans = parser.or(core)?;
+    match parser.pos == argc {
+        true => Ok(ans),
+        false => Err("too many arguments".to_string()),
+    }
+}
+
+fn two_args(core: &mut ShellCore, args: &[String]) -> Result<bool, String> {
+    if args[0] == "!" {
+        return Ok(args[1].is_empty());
+    }
+    match is_unary(&args[0]) {
+        true => unary(core, &args[0], &args[1]),
+        false => Err(format!("{}: unary operator expected", args[0])),
+    }
+}
+
+fn three_args(core: &mut ShellCore, args: &[String]) -> Result<bool, String> {
+    if is_binary(&args[1]) {
+        return binary(&args[0], &args[1], &args[2]);
+    }
+
+    match args[1].as_str() {
+        "-a" => return Ok(!args[0].is_empty() && !args[2].is_empty()),
+        "-o" => return Ok(!args[0].is_empty() || !args[2].is_empty()),
+        _ => {}
+    }
+
+    if args[0] == "!" {
+        return Ok(!two_args(core, &args[1..])?);
+    }
+    if args[0] == "(" && args[2] == ")" {
+        return Ok(!args[1].is_empty());
+    }
+    Err(format!("{}: binary operator expected", args[1]))
+}
+
+fn posix_test(core: &mut ShellCore, args: &[String], argc: usize) -> Result<bool, String> {
+    match argc {
+        0 => Ok(false),
+        1 => Ok(!args[0].is_empty()),
+        2 => two_args(core, args),
+        3 => three_args(core, args),
+        4 if args[0] == "!" => Ok(!three_args(core, &args[1..4])?),
+        4 if args[0] == "(" && args[3] == ")" => two_args(core, &args[1..3]),
+        _ => full_parse(core, args, argc),
+    }
+}
+
+pub fn test(core: &mut ShellCore, args: &[String]) -> i32 {
+    let mut argc = args.len() - 1;
+    if args[0] == "[" {
+        if args.len() < 2 || args[argc] != "]" {
+            return error_(2, &args[0], "missing `]'", core);
+        }
+        argc -= 1;
+    }
+
+    match posix_test(core, &args[1..], argc) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(msg) => error_(2, &args[0], &msg, core),
+    }
+}
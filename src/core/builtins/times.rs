@@ -0,0 +1,22 @@
+//SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
+//SPDX-License-Identifier: BSD-3-Clause
+
+use crate::utils::clock;
+use crate::ShellCore;
+use nix::sys::resource;
+use nix::sys::resource::UsageWho;
+use nix::sys::time::TimeValLike;
+
+pub fn times(_: &mut ShellCore, _: &[String]) -> i32 {
+    for who in [UsageWho::RUSAGE_SELF, UsageWho::RUSAGE_CHILDREN] {
+        let usage = match resource::getrusage(who) {
+            Ok(u) => u,
+            Err(_) => return 1,
+        };
+
+        let user = clock::format_usec(usage.user_time().num_microseconds(), 3, true);
+        let sys = clock::format_usec(usage.system_time().num_microseconds(), 3, true);
+        println!("{user} {sys}");
+    }
+    0
+}
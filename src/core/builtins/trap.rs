@@ -5,115 +5,205 @@
 use crate::error::exec::ExecError;
 use crate::signal;
 use crate::ShellCore;
-use nix::sys::signal::Signal;
-use signal_hook::iterator::Signals;
-use std::str::FromStr;
-use std::sync::atomic::AtomicBool;
-use std::sync::atomic::Ordering::Relaxed;
-use std::sync::Arc;
-use std::{thread, time};
-
-pub fn trap(core: &mut ShellCore, args: &[String]) -> i32 {
-    let args = args.to_owned();
-    if args.len() == 1 {
-        for e in &core.traplist {
-            if e.0 == 0 {
-                println!("trap -- '{}' EXIT", &e.1);
-            } else if let Ok(s) = Signal::try_from(e.0) {
-                println!("trap -- '{}' {}", &e.1, &s);
-            }
-        }
-        return 0;
+use std::collections::BTreeMap;
+
+/* pseudo signal numbers placed after the real signals */
+pub const DEBUG_TRAP: i32 = 65;
+pub const ERR_TRAP: i32 = 66;
+pub const RETURN_TRAP: i32 = 67;
+
+fn pseudo_signal_name(n: i32) -> Option<&'static str> {
+    match n {
+        0 => Some("EXIT"),
+        DEBUG_TRAP => Some("DEBUG"),
+        ERR_TRAP => Some("ERR"),
+        RETURN_TRAP => Some("RETURN"),
+        _ => None,
     }
+}
 
-    if args.len() < 3 {
-        // TODO: print the list of trap entries if args.len() == 1
-        eprintln!("trap: usage: trap arg signal_spec ...");
-        return 2;
+fn signal_name(n: i32) -> String {
+    match pseudo_signal_name(n) {
+        Some(name) => name.to_string(),
+        None => signal::signal_name(n).unwrap_or(n.to_string()),
     }
+}
 
-    let forbiddens = Vec::from(signal_hook::consts::FORBIDDEN);
-    let signals = match args_to_nums(&args[2..], &forbiddens) {
-        Ok(v) => v,
-        Err(e) => {
-            e.print(core);
-            return 1;
-        }
+/* an empty script is kept in core.traps to be distinguished from reset */
+fn set_pseudo_trap(core: &mut ShellCore, n: i32, script: Option<&str>) {
+    match script {
+        Some("") => core.traps.insert(n, String::new()),
+        _ => core.traps.remove(&n),
     };
 
-    let mut exit = false;
-    let mut valid_signals = vec![];
-    for n in &signals {
-        if *n == 0 {
-            exit = true;
-            continue;
-        }
+    let target = match n {
+        0 => &mut core.exit_script,
+        DEBUG_TRAP => &mut core.debug_script,
+        ERR_TRAP => &mut core.err_script,
+        _ => &mut core.return_script,
+    };
+    *target = script.unwrap_or("").to_string();
+}
 
-        if let Ok(s) = TryFrom::try_from(*n) {
-            signal::ignore(s);
-            valid_signals.push(*n);
-            continue;
-        };
+/* signals ignored on entry to a non-interactive shell
+ * can be neither trapped nor reset */
+fn ignored_on_entry(core: &ShellCore, n: i32) -> bool {
+    !core.db.flags.contains('i') && signal::ignored_on_entry(n)
+}
 
-        let msg = format!("trap: {n}: invalid signal specification");
-        return super::error_(1, &args[0], &msg, core);
+fn set_signal_trap(core: &mut ShellCore, n: i32, script: Option<&str>) {
+    if ignored_on_entry(core, n) {
+        return;
     }
 
-    if !valid_signals.is_empty() {
-        for n in &valid_signals {
-            core.traplist.push((*n, args[1].to_string()));
+    match script {
+        None => {
+            core.traps.remove(&n);
+            signal::reset_trap(core, n);
+        }
+        Some("") => {
+            core.traps.insert(n, String::new());
+            signal::ignore_by_trap(core, n);
+        }
+        Some(s) => {
+            core.traps.insert(n, s.to_string());
+            signal::catch(core, n);
         }
-        run_thread(valid_signals, &args[1], core);
     }
+}
 
-    if exit {
-        core.traplist.push((0, args[1].to_string()));
-        core.exit_script = args[1].clone();
+pub fn trap_entries(core: &ShellCore) -> BTreeMap<i32, String> {
+    if let Some(traps) = &core.parent_traps {
+        return traps.clone();
     }
 
-    0
+    let mut ans = core.traps.clone();
+    for n in 1..DEBUG_TRAP {
+        if ignored_on_entry(core, n) {
+            ans.insert(n, String::new());
+        }
+    }
+    for (n, s) in [
+        (0, &core.exit_script),
+        (DEBUG_TRAP, &core.debug_script),
+        (ERR_TRAP, &core.err_script),
+        (RETURN_TRAP, &core.return_script),
+    ] {
+        if !s.is_empty() {
+            ans.insert(n, s.clone());
+        }
+    }
+    ans
 }
 
-fn run_thread(signal_nums: Vec<i32>, script: &str, core: &mut ShellCore) {
-    core.trapped
-        .push((Arc::new(AtomicBool::new(false)), script.to_string()));
-
-    let trap = Arc::clone(&core.trapped.last().unwrap().0);
+fn print_entry(n: i32, script: &str) {
+    let quoted = script.replace('\'', "'\\''");
+    println!("trap -- '{}' {}", &quoted, signal_name(n));
+}
 
-    thread::spawn(move || {
-        let mut signals =
-            Signals::new(signal_nums.clone()).expect("sush(fatal): cannot prepare signal data");
+fn print_traps(core: &mut ShellCore, specs: &[String], only_script: bool) -> i32 {
+    let entries = trap_entries(core);
+    if specs.is_empty() {
+        entries.iter().for_each(|(n, s)| print_entry(*n, s));
+        return 0;
+    }
 
-        loop {
-            thread::sleep(time::Duration::from_millis(5));
-            for signal in signals.pending() {
-                if signal_nums.contains(&signal) {
-                    trap.store(true, Relaxed);
-                }
+    let mut exit_status = 0;
+    for spec in specs {
+        let n = match arg_to_num(spec) {
+            Ok(n) => n,
+            Err(e) => {
+                e.print(core);
+                exit_status = 1;
+                continue;
             }
+        };
+
+        match (entries.get(&n), only_script) {
+            (Some(s), true) => println!("{}", s),
+            (Some(s), false) => print_entry(n, s),
+            _ => {}
         }
-    });
+    }
+    exit_status
 }
 
-fn arg_to_num(arg: &str, forbiddens: &[i32]) -> Result<i32, ExecError> {
-    if arg == "EXIT" || arg == "0" {
-        return Ok(0);
+pub fn trap(core: &mut ShellCore, args: &[String]) -> i32 {
+    let mut args = args.to_owned();
+    match args.get(1).map(|s| s.as_str()) {
+        Some("-l") => {
+            signal::print_signal_list();
+            return 0;
+        }
+        Some("-p") => return print_traps(core, &args[2..], false),
+        Some("-P") if args.len() > 2 => return print_traps(core, &args[2..], true),
+        Some("-P") => {
+            let msg = "-P: option requires an argument";
+            return super::error_(2, &args[0], msg, core);
+        }
+        Some("--") => {
+            args.remove(1);
+        }
+        _ => {}
     }
 
-    if let Ok(n) = Signal::from_str(arg) {
-        return Ok(n as i32);
+    if args.len() == 1 {
+        return print_traps(core, &[], false);
     }
 
-    if let Ok(n) = Signal::from_str(&("SIG".to_owned() + arg)) {
-        return Ok(n as i32);
-    }
+    /* `trap SIG ...` and `trap N ...` reset the conditions */
+    let script = match args.len() == 2 || args[1].parse::<u32>().is_ok() {
+        true => None,
+        false => Some(args.remove(1)),
+    };
+    let script = match script.as_deref() {
+        Some("-") => None,
+        s => s,
+    };
+
+    let mut exit_status = 0;
+    for spec in &args[1..] {
+        let n = match arg_to_num(spec) {
+            Ok(n) => n,
+            Err(e) => {
+                e.print(core);
+                exit_status = 1;
+                continue;
+            }
+        };
 
-    if let Ok(n) = arg.parse::<i32>() {
-        if forbiddens.contains(&n) {
-            return Err(ExecError::Other(format!(
-                "trap: {arg}: forbidden signal for trap"
-            )));
+        if pseudo_signal_name(n).is_some() {
+            set_pseudo_trap(core, n, script);
+        } else if signal::signal_name(n).is_some() {
+            set_signal_trap(core, n, script);
+        } else {
+            let msg = format!("{spec}: invalid signal specification");
+            exit_status = super::error_(1, &args[0], &msg, core);
+            continue;
         }
+        core.parent_traps = None;
+    }
+
+    exit_status
+}
+
+fn arg_to_num(arg: &str) -> Result<i32, ExecError> {
+    let upper = arg.to_uppercase();
+    match upper.as_str() {
+        "EXIT" | "0" => return Ok(0),
+        "DEBUG" => return Ok(DEBUG_TRAP),
+        "ERR" => return Ok(ERR_TRAP),
+        "RETURN" => return Ok(RETURN_TRAP),
+        _ => {}
+    }
+
+    if arg.parse::<i32>().is_ok_and(|n| signal_hook::consts::FORBIDDEN.contains(&n)) {
+        return Err(ExecError::Other(format!(
+            "trap: {arg}: forbidden signal for trap"
+        )));
+    }
+
+    if let Some(n) = signal::signal_number(arg) {
         return Ok(n);
     }
 
@@ -121,12 +211,3 @@ fn arg_to_num(arg: &str, forbiddens: &[i32]) -> Result<i32, ExecError> {
         "trap: {arg}: invalid signal specification"
     )))
 }
-
-fn args_to_nums(args: &[String], forbiddens: &[i32]) -> Result<Vec<i32>, ExecError> {
-    let mut ans = vec![];
-    for a in args {
-        let n = arg_to_num(a, forbiddens)?;
-        ans.push(n);
-    }
-    Ok(ans)
-}
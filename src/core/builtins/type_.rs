@@ -2,189 +2,174 @@
 //SPDX-FileCopyrightText: 2023 @caro@mi.shellgei.org
 //SPDX-License-Identifier: BSD-3-Clause
 
-use crate::utils::{arg, file};
-use crate::{file_check, utils, ShellCore};
-
-fn type_no_opt_sub(core: &mut ShellCore, com: &String) -> i32 {
-    if core.shopts.query("expand_aliases") && core.db.has_array_value("BASH_ALIASES", com) {
-        let alias = core.db.get_elem("BASH_ALIASES", com).unwrap();
-        println!("{} is aliased to `{}'", &com, &alias);
-        return 0;
-    }
-    if utils::reserved(com) {
-        println!("{} is a shell keyword", &com);
-        return 0;
-    }
-    if core.db.functions.contains_key(com) {
-        println!("{} is a function", &com);
-        if let Some(val) = core.db.functions.get_mut(com) {
-            val.pretty_print(0);
-        };
-        return 0;
-    }
-    if core.builtins.contains_key(com) {
-        println!("{com} is a shell builtin");
-        return 0;
-    }
-    if let Some(path) = file::search_command(com) {
-        println!("{} is {}", com, &path);
-        return 0;
-    }
-    if file_check::is_executable(com) {
-        println!("{com} is {com}");
-        return 0;
-    }
-
-    let s = format!("{}: not found", &com);
-    super::error_(1, "type", &s, core)
+use super::error_;
+use crate::utils::{file, file_check};
+use crate::{utils, ShellCore};
+
+const USAGE: &str = "type: usage: type [-afptP] name [name ...]";
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Form {
+    #[default]
+    Long, //type, command -V
+    Type,     //type -t
+    Reusable, //command -v
+    PathOnly, //type -p, type -P
 }
 
-fn type_no_opt(core: &mut ShellCore, args: &[String]) -> i32 {
-    let mut exit_status = 0;
-    for a in args {
-        exit_status += type_no_opt_sub(core, a);
-    }
-    if exit_status > 1 {
-        exit_status = 1;
-    }
-    exit_status
+/* how a command name is described by type and command -v/-V */
+#[derive(Clone, Copy, Default)]
+pub struct Describe {
+    pub form: Form,
+    pub all: bool,
+    pub no_funcs: bool,
+    pub force_path: bool,
+    pub std_path: bool,
+    pub abs_path: bool,
 }
 
-fn type_t(core: &mut ShellCore, args: &[String]) -> i32 {
-    let mut exit_status = 0;
-    for a in args {
-        exit_status += type_t_sub(core, a);
-    }
-    if exit_status > 1 {
-        exit_status = 1;
+impl Describe {
+    fn print(&self, long: &str, reusable: &str, tp: &str) {
+        match self.form {
+            Form::Long => println!("{long}"),
+            Form::Type => println!("{tp}"),
+            Form::Reusable => println!("{reusable}"),
+            Form::PathOnly => {}
+        }
     }
-    exit_status
-}
 
-fn type_t_sub(core: &mut ShellCore, com: &String) -> i32 {
-    if core.shopts.query("expand_aliases") && core.db.has_array_value("BASH_ALIASES", com) {
-        println!("alias");
-        return 0;
-    }
-    if utils::reserved(com) {
-        println!("keyword");
-        return 0;
-    }
-    if core.db.functions.contains_key(com) {
-        println!("function");
-        return 0;
-    }
-    if core.builtins.contains_key(com) {
-        println!("builtin");
-        return 0;
-    }
-    if file::search_command(com).is_some() || file_check::is_executable(com) {
-        println!("file");
-        return 0;
+    fn print_path(&self, path: &str, long: &str) {
+        match self.form {
+            Form::Long => println!("{long}"),
+            Form::Type => println!("file"),
+            _ => println!("{path}"),
+        }
     }
 
-    1
-}
+    /* returns true if COM is found */
+    pub fn describe(&self, core: &mut ShellCore, com: &str) -> bool {
+        let mut found = false;
+
+        if !self.force_path
+            && core.shopts.query("expand_aliases")
+            && core.db.has_array_value("BASH_ALIASES", com)
+        {
+            let alias = core.db.get_elem("BASH_ALIASES", com).unwrap_or_default();
+            let long = format!("{com} is aliased to `{alias}'");
+            let reusable = format!("alias {com}='{}'", alias.replace('\'', "'\\''"));
+            self.print(&long, &reusable, "alias");
+            found = true;
+            if !self.all {
+                return true;
+            }
+        }
 
-fn type_p(core: &mut ShellCore, args: &[String]) -> i32 {
-    let mut exit_status = 0;
-    for a in args {
-        exit_status += type_p_sub(core, a);
-    }
-    if exit_status > 1 {
-        exit_status = 1;
-    }
-    exit_status
-}
+        if !self.force_path && utils::reserved(com) {
+            self.print(&format!("{com} is a shell keyword"), com, "keyword");
+            found = true;
+            if !self.all {
+                return true;
+            }
+        }
 
-fn type_large_p(core: &mut ShellCore, args: &[String]) -> i32 {
-    let mut exit_status = 0;
-    for a in args {
-        exit_status += type_large_p_sub(core, a);
-    }
-    if exit_status > 1 {
-        exit_status = 1;
-    }
-    exit_status
-}
+        if !self.force_path && !self.no_funcs && core.db.functions.contains_key(com) {
+            self.print(&format!("{com} is a function"), com, "function");
+            if self.form == Form::Long {
+                core.db.functions.get_mut(com).unwrap().pretty_print(0);
+            }
+            found = true;
+            if !self.all {
+                return true;
+            }
+        }
 
-fn type_p_sub(core: &mut ShellCore, com: &String) -> i32 {
-    if core.db.has_array_value("BASH_ALIASES", com)
-        || core.db.functions.contains_key(com)
-        || utils::reserved(com)
-        || core.builtins.contains_key(com)
-    {
-        return 0;
-    }
+        let builtin = core.builtins.contains_key(com) || core.subst_builtins.contains_key(com);
+        if !self.force_path && builtin {
+            self.print(&format!("{com} is a shell builtin"), com, "builtin");
+            found = true;
+            if !self.all {
+                return true;
+            }
+        }
 
-    if let Ok(path) = core.db.get_elem("BASH_CMDS", com) {
-        if !path.is_empty() {
-            println!("{}", &path);
-            return 0;
+        /* a name with a slash is not searched in the hash table and PATH */
+        if com.contains('/') {
+            if file_check::is_executable(com) && !file_check::is_dir(com) {
+                self.print_path(com, &format!("{com} is {com}"));
+                return true;
+            }
+            return found;
         }
-    }
 
-    if let Some(path) = file::search_command(com) {
-        println!("{}", &path);
-        return 0;
-    }
-    if file_check::is_executable(com) {
-        println!("{com}");
-        return 0;
-    }
-    1
-}
+        if !self.all || self.force_path {
+            let hashed = core.db.get_elem("BASH_CMDS", com).unwrap_or_default();
+            if !hashed.is_empty() {
+                self.print_path(&hashed, &format!("{com} is hashed ({hashed})"));
+                return true;
+            }
+        }
 
-fn type_large_p_sub(core: &mut ShellCore, com: &String) -> i32 {
-    let mut es = 1;
-    if core.db.has_array_value("BASH_ALIASES", com)
-        || core.db.functions.contains_key(com)
-        || utils::reserved(com)
-        || core.builtins.contains_key(com)
-    {
-        es = 0;
-    }
+        let paths = match self.std_path {
+            true => file::standard_path(),
+            false => core.db.get_param("PATH").unwrap_or_default(),
+        };
 
-    if let Some(path) = file::search_command(com) {
-        println!("{}", &path);
-        return 0;
-    }
-    if file_check::is_executable(com) {
-        println!("{com}");
-        return 0;
-    }
+        for mut path in file::search_commands(com, &paths) {
+            if self.abs_path && !path.starts_with('/') {
+                let cwd = core.get_current_directory().unwrap_or_default();
+                path = format!("{}/{}", cwd.display(), path.trim_start_matches("./"));
+            }
+            self.print_path(&path, &format!("{com} is {path}"));
+            found = true;
+            if !self.all {
+                break;
+            }
+        }
 
-    es
+        found
+    }
 }
 
 pub fn type_(core: &mut ShellCore, args: &[String]) -> i32 {
-    if args.len() < 2 {
-        return 0;
-    }
+    let mut desc = Describe::default();
+    let mut i = 1;
 
-    let mut args = arg::dissolve_options(args);
+    while i < args.len() && args[i].starts_with('-') && args[i] != "-" {
+        if args[i] == "--" {
+            i += 1;
+            break;
+        }
 
-    let t_option = arg::consume_arg("-t", &mut args);
-    if t_option {
-        if args.len() > 1 && args[1] == "--" {
-            args.remove(1);
+        for c in args[i][1..].chars() {
+            match c {
+                'a' => desc.all = true,
+                'f' => desc.no_funcs = true,
+                'p' => desc.form = Form::PathOnly,
+                't' => desc.form = Form::Type,
+                'P' => {
+                    desc.form = Form::PathOnly;
+                    desc.force_path = true;
+                }
+                _ => {
+                    error_(2, &args[0], &format!("-{c}: invalid option"), core);
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            }
         }
-        return type_t(core, &args[1..]);
+        i += 1;
     }
-    let p_option = arg::consume_arg("-p", &mut args);
-    if p_option {
-        if args.len() > 1 && args[1] == "--" {
-            args.remove(1);
+
+    let mut exit_status = 0;
+    for com in &args[i..] {
+        if desc.describe(core, com) {
+            continue;
         }
-        return type_p(core, &args[1..]);
-    }
-    let large_p_option = arg::consume_arg("-P", &mut args);
-    if large_p_option {
-        if args[1] == "--" {
-            args.remove(1);
+
+        exit_status = 1;
+        if desc.form == Form::Long {
+            error_(1, &args[0], &format!("{com}: not found"), core);
         }
-        return type_large_p(core, &args[1..]);
     }
-
-    type_no_opt(core, &args[1..])
+    exit_status
 }
@@ -0,0 +1,144 @@
+//SPDX-FileCopyrightText: 2025 Ryuichi Ueda <ryuichiueda@gmail.com>
+//SPDX-License-Identifier: BSD-3-Clause
+
+use super::error_;
+use crate::ShellCore;
+use nix::sys::stat;
+use nix::sys::stat::Mode;
+
+const USAGE: &str = "umask: usage: umask [-p] [-S] [mode]";
+
+fn get_umask() -> u32 {
+    let mask = stat::umask(Mode::empty());
+    stat::umask(mask);
+    mask.bits() as u32
+}
+
+fn set_umask(mask: u32) {
+    stat::umask(Mode::from_bits_truncate((mask & 0o777) as _));
+}
+
+fn symbolic_string(mask: u32) -> String {
+    let perm = !mask & 0o777;
+    let mut ans = vec![];
+    for (who, shift) in [("u", 6), ("g", 3), ("o", 0)] {
+        let mut s = format!("{who}=");
+        for (c, bit) in [('r', 4), ('w', 2), ('x', 1)] {
+            if (perm >> shift) & bit != 0 {
+                s.push(c);
+            }
+        }
+        ans.push(s);
+    }
+    ans.join(",")
+}
+
+fn parse_octal(mode: &str) -> Result<u32, String> {
+    match u32::from_str_radix(mode, 8) {
+        Ok(n) if n <= 0o7777 => Ok(n),
+        _ => Err(format!("{mode}: octal number out of range")),
+    }
+}
+
+/* applies clauses like `u=rwx,g-w,a+r' to the permissions the mask leaves */
+fn parse_symbolic(mode: &str, mask: u32) -> Result<u32, String> {
+    let mut perm = !mask & 0o777;
+    let mut chars = mode.chars().peekable();
+
+    loop {
+        let mut who = 0;
+        while let Some(c) = chars.next_if(|c| "ugoa".contains(*c)) {
+            who |= match c {
+                'u' => 0o700,
+                'g' => 0o070,
+                'o' => 0o007,
+                _ => 0o777,
+            };
+        }
+        if who == 0 {
+            who = 0o777;
+        }
+
+        let op = match chars.next() {
+            Some(c) if "+-=".contains(c) => c,
+            Some(c) => return Err(format!("`{c}': invalid symbolic mode operator")),
+            None => return Err("`\0': invalid symbolic mode operator".to_string()),
+        };
+
+        let mut bits = 0;
+        while let Some(c) = chars.next_if(|c| *c != ',') {
+            bits |= match c {
+                'r' => 0o444,
+                'w' => 0o222,
+                'x' => 0o111,
+                _ => return Err(format!("`{c}': invalid symbolic mode character")),
+            };
+        }
+
+        match op {
+            '+' => perm |= who & bits,
+            '-' => perm &= !(who & bits),
+            _ => perm = (perm & !who) | (who & bits),
+        }
+
+        if chars.next().is_none() {
+            return Ok(!perm & 0o777);
+        }
+    }
+}
+
+pub fn umask(core: &mut ShellCore, args: &[String]) -> i32 {
+    let mut symbolic = false;
+    let mut reusable = false;
+    let mut i = 1;
+
+    while i < args.len() && args[i].starts_with('-') && args[i] != "-" {
+        if args[i] == "--" {
+            i += 1;
+            break;
+        }
+
+        for c in args[i][1..].chars() {
+            match c {
+                'S' => symbolic = true,
+                'p' => reusable = true,
+                _ => {
+                    error_(2, &args[0], &format!("-{c}: invalid option"), core);
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let mask = get_umask();
+    let mode = match args.get(i) {
+        Some(mode) => mode,
+        None => {
+            match (symbolic, reusable) {
+                (true, true) => println!("umask -S {}", symbolic_string(mask)),
+                (true, false) => println!("{}", symbolic_string(mask)),
+                (false, true) => println!("umask {mask:04o}"),
+                (false, false) => println!("{mask:04o}"),
+            }
+            return 0;
+        }
+    };
+
+    let new_mask = match mode.starts_with(|c: char| c.is_ascii_digit()) {
+        true => parse_octal(mode),
+        false => parse_symbolic(mode, mask),
+    };
+
+    match new_mask {
+        Ok(m) => {
+            set_umask(m);
+            if symbolic {
+                println!("{}", symbolic_string(m & 0o777));
+            }
+            0
+        }
+        Err(msg) => error_(1, &args[0], &msg, core),
+    }
+}
@@ -73,6 +73,12 @@ impl DataBase {
         }
     }
 
+    /* the paths of commands are forgotten when PATH changes */
+    pub fn flush_hash(&mut self) {
+        self.init("BASH_CMDS", 0);
+        self.hash_counter.clear();
+    }
+
     pub fn int_to_str_type(&mut self, name: &str, scope: usize) -> Result<(), ExecError> {
         let scope_len = self.params.len();
         for ly in scope..scope_len {
@@ -25,6 +25,10 @@ impl DataBase {
             self.position_parameters[n][0] += val;
         }
 
+        if name == "PATH" {
+            self.flush_hash();
+        }
+
         if !self.flags.contains('r')
             && (self.flags.contains('a') || self.has_flag(name, 'x'))
             && env::var(name).is_err()
@@ -42,7 +42,8 @@ impl DataBase {
         self.init_array("BASH_ARGC", Some(vec![]), None, false)?;
         self.init_array("BASH_ARGV", Some(vec![]), None, false)?;
         self.init_array("BASH_LINENO", Some(vec![]), None, false)?;
-        self.init_array("DIRSTACK", Some(vec![]), None, false)?;
+        let cwd = env::current_dir().map(|p| p.display().to_string()).unwrap_or_default();
+        self.init_array("DIRSTACK", Some(vec![cwd]), None, false)?;
 
         self.init_assoc("BASH_ALIASES", None, true, false)?;
         self.init_assoc("BASH_CMDS", None, true, false)?;
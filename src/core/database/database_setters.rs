@@ -26,6 +26,10 @@ impl DataBase {
             self.position_parameters[n][0] = val.to_string();
         }
 
+        if name == "PATH" {
+            self.flush_hash();
+        }
+
         if !self.flags.contains('r')
         && (self.flags.contains('a')
        || self.has_flag(name, 'x')) {
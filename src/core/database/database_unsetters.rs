@@ -59,6 +59,10 @@ impl DataBase {
             return Ok(false);
         }
 
+        if name == "PATH" {
+            self.flush_hash();
+        }
+
         let mut res = false;
 
         if let Some(scope) = called_scope {
@@ -4,11 +4,11 @@
 extern crate libc;
 use libc::dup2;
 use libc::fcntl;
-use libc::{F_GETFD, F_DUPFD_CLOEXEC};
+use libc::{F_GETFD, F_SETFD, F_DUPFD_CLOEXEC};
 
 use crate::error::exec::ExecError;
 use nix::unistd::Pid;
-use std::os::fd::{OwnedFd, FromRawFd, RawFd};
+use std::os::fd::{OwnedFd, FromRawFd, IntoRawFd, RawFd};
 use nix::unistd;
 use std::os::fd::AsRawFd;
 use std::fs::File;
@@ -39,6 +39,18 @@ impl FileDescriptors {
         Ok(fd)
     }
 
+    /* fds opened by redirections are also kept except std{in, out, err} */
+    fn register(&mut self, fd: RawFd) {
+        if fd <= 2 || fd >= 256 {
+            return;
+        }
+
+        let new = unsafe { OwnedFd::from_raw_fd(fd) };
+        if let Some(old) = self.fds[fd as usize].replace(new) {
+            let _ = old.into_raw_fd(); // the number is already reused by dup2
+        }
+    }
+
     pub fn tcsetpgrp(&mut self, fd: RawFd, pgid: Pid) -> Result<(), ExecError> {
         if let Some(fd) = self.fds[fd as usize].as_mut() {
             return Ok(unistd::tcsetpgrp(fd, pgid)?);
@@ -85,12 +97,20 @@ impl FileDescriptors {
             return Ok(());
         }
 
+        /* a file opened for a redirection can already have the number */
+        if from == to {
+            unsafe{fcntl(to, F_SETFD, 0)};
+            self.register(to);
+            return Ok(());
+        }
+
         if unsafe{dup2(from, to)} < 0 {
             return Err(ExecError::Other("dup2 error".to_string()));
         }
 
         //unistd::dup2(from, to)?;
         self.close(from);
+        self.register(to);
         Ok(())
     }
 
@@ -104,6 +124,7 @@ impl FileDescriptors {
             return Err(ExecError:: BadFd(from));
         }
 
+        self.register(to);
         Ok(())
     }
 
@@ -8,6 +8,7 @@ use nix::sys::wait;
 use nix::sys::wait::{WaitPidFlag, WaitStatus};
 use nix::unistd;
 use nix::unistd::Pid;
+use std::sync::atomic::Ordering::Relaxed;
 
 #[derive(Debug, Default)]
 pub struct JobEntry {
@@ -20,30 +21,22 @@ pub struct JobEntry {
     pub no_control: bool,
     pub coproc_name: Option<String>,
     pub coproc_fds: Vec<i32>,
+    owner: Option<Pid>,
 }
 
-fn wait_nonblock(pid: &Pid, status: &mut WaitStatus, coproc: bool) -> Result<(), ExecError> {
+fn wait_nonblock(pid: &Pid, status: &mut WaitStatus) -> Result<(), ExecError> {
     let waitflags = WaitPidFlag::WNOHANG | WaitPidFlag::WUNTRACED | WaitPidFlag::WCONTINUED;
 
-    match wait::waitpid(*pid, Some(waitflags)) {
-        Ok(s) => {
-            if s != WaitStatus::StillAlive || !still(status) {
-                *status = s;
-            }
-        },
-        Err(e) => {
-            if ! coproc {
-                return Err(ExecError::Errno(e));
-            }
-            *status = WaitStatus::Exited(*pid, 0);
-        },
+    let s = wait::waitpid(*pid, Some(waitflags))?;
+    if s != WaitStatus::StillAlive || !still(status) {
+        *status = s;
     }
 
     Ok(())
 }
 
 fn wait_block(pid: &Pid, status: &mut WaitStatus) -> Result<i32, ExecError> {
-    *status = wait::waitpid(*pid, Some(WaitPidFlag::WUNTRACED))?;
+    *status = crate::signal::waitpid(*pid, Some(WaitPidFlag::WUNTRACED))?;
     let exit_status = match status {
         WaitStatus::Exited(_, es) => *es,
         WaitStatus::Stopped(_, _) => 148,
@@ -75,10 +68,16 @@ impl JobEntry {
             proc_statuses: statuses.to_vec(),
             display_status: status.to_string(),
             text: text.to_string(),
+            owner: Some(unistd::getpid()),
             ..Default::default()
         }
     }
 
+    /* false for the jobs inherited from the parent shell */
+    pub fn is_own(&self) -> bool {
+        self.owner == Some(unistd::getpid())
+    }
+
     pub fn update_status(&mut self, wait: bool, check_done: bool) -> Result<i32, ExecError> {
         let mut exit_status = 0;
         let before = self.proc_statuses[0];
@@ -87,7 +86,7 @@ impl JobEntry {
                 match wait {
                     true => exit_status = wait_block(pid, status)?,
                     false => {
-                        wait_nonblock(pid, status, self.coproc_name.is_some())?;
+                        wait_nonblock(pid, status)?;
                     }
                 }
             }
@@ -240,23 +239,24 @@ impl JobEntry {
 }
 
 impl ShellCore {
-    fn close_coproc(&mut self, pos: usize) {
-        let name = self.job_table[pos].coproc_name.clone().unwrap();
-        let _ = self.db.unset(&name, None, false);
-        let _ = self.db.unset(&(name.clone() + "_PID"), None, false);
-
-        if let Ok(fd0) = self.db.get_elem(&name, "0") {
-            if let Ok(n) = fd0.parse::<i32>() {
-                let _ = unsafe{libc::close(n)};
-            }
-        }
-        if let Ok(fd1) = self.db.get_elem(&name, "1") {
-            if let Ok(n) = fd1.parse::<i32>() {
-                let _ = unsafe{libc::close(n)};
-            }
+    pub fn close_coproc(&mut self, pos: usize) {
+        let entry = &mut self.job_table[pos];
+        let name = match entry.coproc_name.take() {
+            Some(name) => name,
+            None => return,
+        };
+
+        for fd in std::mem::take(&mut entry.coproc_fds) {
+            self.fds.close(fd);
         }
 
-        let _ = self.db.unset(&(name), None, false);
+        /* the variables may already belong to a newer coproc */
+        let pid_name = name.clone() + "_PID";
+        let pid = entry.pids[0].to_string();
+        if self.db.get_param(&pid_name).is_ok_and(|p| p == pid) {
+            let _ = self.db.unset(&name, None, false);
+            let _ = self.db.unset(&pid_name, None, false);
+        }
     }
 
     pub fn jobtable_check_status(&mut self) -> Result<(), ExecError> {
@@ -272,9 +272,7 @@ impl ShellCore {
                 stopped.push(i);
             }
 
-            if  table.coproc_name.is_some() 
-            && (table.display_status == "Done" 
-                || table.display_status == "Killed") {
+            if table.coproc_name.is_some() && !still(&table.proc_statuses[0]) {
                 self.close_coproc(i);
             }
         }
@@ -288,6 +286,66 @@ impl ShellCore {
         Ok(())
     }
 
+    /* called when SIGCHLD has arrived; returns true if some job changed */
+    pub fn jobtable_reap(&mut self) -> bool {
+        if !crate::signal::take_child_change() || self.jobtable_check_status().is_err() {
+            return false;
+        }
+        self.job_table.iter().any(|e| e.change)
+    }
+
+    /* Blocks until one of the jobs at IDS (any job if empty) ends or stops.
+     * Returns its position and exit status, or None on SIGINT. */
+    pub fn wait_any_job(&mut self, ids: &[usize], f_opt: bool) -> Option<(usize, i32)> {
+        loop {
+            for (i, job) in self.job_table.iter_mut().enumerate() {
+                if !ids.contains(&i) && !ids.is_empty() {
+                    continue;
+                }
+
+                if let Ok(es) = job.update_status(false, true) {
+                    if job.display_status == "Done"
+                        || job.display_status == "Killed"
+                        || (job.display_status == "Stopped" && !f_opt)
+                    {
+                        return Some((i, es));
+                    }
+                }
+            }
+
+            if self.sigint.load(Relaxed) {
+                return None;
+            }
+            crate::signal::wait_event(None);
+        }
+    }
+
+    /* sush extension: blocks until fewer than MAXJOBS jobs of this
+     * shell process are running; returns false on SIGINT */
+    pub fn wait_job_slot(&mut self) -> bool {
+        let max = match self.db.get_param("MAXJOBS").map(|s| s.parse::<usize>()) {
+            Ok(Ok(n)) if n > 0 => n,
+            _ => return true,
+        };
+
+        loop {
+            let running: Vec<usize> = self
+                .job_table
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| e.is_own() && e.display_status == "Running")
+                .map(|(i, _)| i)
+                .collect();
+            if running.len() < max {
+                return true;
+            }
+
+            if self.wait_any_job(&running, true).is_none() {
+                return false;
+            }
+        }
+    }
+
     pub fn jobtable_print_status_change(&mut self) {
         if self.is_subshell {
             return;
@@ -14,11 +14,15 @@ impl Options {
     pub fn new_as_basic_opts() -> Options {
         let mut options = Options::default();
         options.opts.insert("allexport".to_string(), false);
+        options.opts.insert("errtrace".to_string(), false);
+        options.opts.insert("functrace".to_string(), false);
         options.opts.insert("pipefail".to_string(), false);
         options.opts.insert("monitor".to_string(), true);
         options.opts.insert("noclobber".to_string(), false);
         options.opts.insert("noglob".to_string(), false);
+        options.opts.insert("notify".to_string(), false);
         options.opts.insert("onecmd".to_string(), false);
+        options.opts.insert("physical".to_string(), false);
         options.opts.insert("posix".to_string(), false);
         options.opts.insert("history".to_string(), false); //TODO: still dummy
         options
@@ -98,6 +102,12 @@ impl Options {
             "assoc_expand_once",
             "localvar_inherit",
             "localvar_unset",
+            "extdebug",
+            "autocd",
+            "cdable_vars",
+            "cdspell",
+            "dirspell",
+            "checkhash",
         ]
         .iter()
         .map(|s| s.to_string())
@@ -5,7 +5,7 @@ use super::{Command, Redirect};
 use crate::elements::expr::arithmetic::ArithmeticExpr;
 use crate::error::exec::ExecError;
 use crate::error::parse::ParseError;
-use crate::{Feeder, ShellCore};
+use crate::{signal, Feeder, ShellCore};
 
 #[derive(Debug, Clone, Default)]
 pub struct ArithmeticCommand {
@@ -18,6 +18,9 @@ pub struct ArithmeticCommand {
 
 impl Command for ArithmeticCommand {
     fn run(&mut self, core: &mut ShellCore, _: bool) -> Result<(), ExecError> {
+        if !signal::run_debug_trap(core, self.text.trim()) {
+            return Ok(());
+        }
         let mut err = None;
 
         let exit_status = match self.eval(core) {
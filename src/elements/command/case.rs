@@ -7,7 +7,7 @@ use crate::elements::word::Word;
 use crate::error::exec::ExecError;
 use crate::error::parse::ParseError;
 use crate::utils::glob;
-use crate::{Feeder, Script, ShellCore};
+use crate::{signal, Feeder, Script, ShellCore};
 
 #[derive(Debug, Clone, Default)]
 pub struct CaseCommand {
@@ -25,6 +25,9 @@ impl Command for CaseCommand {
             .set_param("LINENO", &self.lineno.to_string(), None)?;
         let mut next = false;
         let word = self.word.clone().unwrap();
+        if !signal::run_debug_trap(core, &format!("case {} in", word.text)) {
+            return Ok(());
+        }
 
         if core.db.flags.contains('x') {
             let ps4 = core.get_ps4();
@@ -1,7 +1,6 @@
 //SPDX-FileCopyrightText: 2025 Ryuichi Ueda ryuichiueda@gmail.com
 //SPDX-License-Identifier: BSD-3-Clause
 
-use libc;
 use super::{Command, Pipe, Redirect};
 use crate::elements::command;
 use crate::elements::command::{
@@ -11,11 +10,9 @@ use crate::error::exec::ExecError;
 use crate::error::parse::ParseError;
 use crate::utils;
 use crate::{Feeder, ShellCore};
-//use nix::unistd;
 use nix::unistd::Pid;
-use nix::sys::wait;
 use nix::sys::wait::WaitStatus;
-use std::thread;
+use std::os::fd::RawFd;
 use crate::core::jobtable::JobEntry;
 
 #[derive(Debug, Clone, Default)]
@@ -43,18 +40,19 @@ impl Command for Coprocess {
 
         let mut prevp = Pipe::new("|".to_string());
         prevp.set(-1, pgid, core);
-
-        prevp.send = unsafe{libc::fcntl(prevp.send, libc::F_DUPFD_CLOEXEC, 60)};
-        prevp.recv = unsafe{libc::fcntl(prevp.recv, libc::F_DUPFD_CLOEXEC, 60)};
+        prevp.send = Self::move_fd(core, prevp.send)?;
+        prevp.recv = Self::move_fd(core, prevp.recv)?;
 
         let mut lastp = Pipe::new("|".to_string());
         lastp.set(prevp.recv, pgid, core);
+        lastp.send = Self::move_fd(core, lastp.send)?;
+        lastp.recv = Self::move_fd(core, lastp.recv)?;
 
-        lastp.send = unsafe{libc::fcntl(lastp.send, libc::F_DUPFD_CLOEXEC, 60)};
-        lastp.recv = unsafe{libc::fcntl(lastp.recv, libc::F_DUPFD_CLOEXEC, 60)};
+        lastp.coproc_fds = core.job_table.iter().flat_map(|e| e.coproc_fds.clone()).collect();
+        lastp.coproc_fds.push(prevp.send);
 
         let pid = com.exec(core, &mut lastp)?.unwrap();
-        let fds = vec![lastp.recv.clone(), prevp.send.clone()];
+        let fds = vec![lastp.recv, prevp.send];
         let fds_str = Some(vec![lastp.recv.to_string(), prevp.send.to_string()]);
 
         let _ = core.db.init_array(&self.name, fds_str, Some(0), true);
@@ -80,7 +78,7 @@ impl Command for Coprocess {
             new_job_id,
         );
         entry.coproc_name = Some(self.name.clone());
-        entry.coproc_fds = fds.clone();
+        entry.coproc_fds = fds;
 
         if let Some(pid) = core.get_jobentry_pid_by_coproc_name(&self.name) {
             let msg = format!("warning: execute_coproc: coproc [{}:{}] still exists",
@@ -96,17 +94,6 @@ impl Command for Coprocess {
         core.job_table.push(entry);
         core.tty_fd = backup;
 
-        thread::spawn(move || {
-            match wait::waitpid(pid, None) {
-                Ok(WaitStatus::Exited(_, _)) | Err(_) => {
-                    let _ = unsafe{libc::close(fds[0])};
-                    let _ = unsafe{libc::close(fds[1])};
-                    return;
-                }
-                _ => {},
-            }
-        });
-
         Ok(None)
     }
 
@@ -138,6 +125,13 @@ impl Command for Coprocess {
 }
 
 impl Coprocess {
+    /* moves a pipe end to 60 or above so as not to collide with user FDs */
+    fn move_fd(core: &mut ShellCore, fd: RawFd) -> Result<RawFd, ExecError> {
+        let ans = core.fds.dupfd_cloexec(fd, 60)?;
+        core.fds.close(fd);
+        Ok(ans)
+    }
+
     pub fn pretty_print(&mut self, indent_num: usize) {
         println!("{} () ", self.name);
         for com in self.command.iter_mut() {
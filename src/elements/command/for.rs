@@ -1,7 +1,7 @@
 //SPDX-FileCopyrightText: 2022 Ryuichi Ueda ryuichiueda@gmail.com
 //SPDX-License-Identifier: BSD-3-Clause
 
-use crate::{Feeder, Script, ShellCore};
+use crate::{signal, Feeder, Script, ShellCore};
 
 use super::{Command, Redirect};
 use crate::elements::command;
@@ -97,6 +97,9 @@ impl ForCommand {
             if core.return_flag {
                 return false;
             }
+            if !signal::run_debug_trap(core, &self.header_text()) {
+                continue;
+            }
 
             if core.db.has_flag(&self.name, 'n') {
                 if let Err(e) = core.db.set_nameref(&self.name, &p, None) {
@@ -132,7 +135,21 @@ impl ForCommand {
         true
     }
 
+    fn header_text(&self) -> String {
+        let values = self.values.iter().map(|w| w.text.clone());
+        let values: Vec<String> = values.collect();
+        match self.has_in {
+            true => format!("for {} in {}", self.name, values.join(" ")),
+            false => format!("for {}", self.name),
+        }
+    }
+
     fn eval_arithmetic(a: &mut Option<ArithmeticExpr>, core: &mut ShellCore) -> (bool, String) {
+        if let Some(arith) = a {
+            let text = format!("(({}))", arith.text.trim());
+            signal::run_debug_trap(core, &text);
+        }
+
         match a {
             None => (true, "1".to_string()),
             Some(arith) => match arith.eval(core) {
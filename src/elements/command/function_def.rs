@@ -6,7 +6,7 @@ use crate::elements::command;
 use crate::elements::command::{BraceCommand, IfCommand, ParenCommand, WhileCommand};
 use crate::error::exec::ExecError;
 use crate::error::parse::ParseError;
-use crate::utils;
+use crate::{signal, utils};
 use crate::{Feeder, ShellCore};
 use nix::unistd::Pid;
 
@@ -96,13 +96,38 @@ impl FunctionDefinition {
 
         let mut dummy = Pipe::new("|".to_string());
 
+        let err_script = match core.options.query("errtrace") {
+            true => String::new(),
+            false => std::mem::take(&mut core.err_script),
+        };
+        let (debug_script, return_script) = match core.options.query("functrace") {
+            true => (String::new(), String::new()),
+            false => (std::mem::take(&mut core.debug_script),
+                      std::mem::take(&mut core.return_script)),
+        };
+
         core.source_function_level += 1;
-        if let Err(e) = self.command.as_mut().unwrap().exec(core, &mut dummy) {
-            e.print(core);
+        let command = core.db.get_param("BASH_COMMAND").unwrap_or_default();
+        if signal::run_debug_trap(core, &command) {
+            let result = self.command.as_mut().unwrap().exec(core, &mut dummy);
+            if let Err(e) = result {
+                e.print(core);
+            }
         }
         core.return_flag = false;
+        signal::run_return_trap(core);
         core.source_function_level -= 1;
 
+        if core.err_script.is_empty() {
+            core.err_script = err_script;
+        }
+        if core.debug_script.is_empty() {
+            core.debug_script = debug_script;
+        }
+        if core.return_script.is_empty() {
+            core.return_script = return_script;
+        }
+
         core.db.position_parameters.pop();
 
         array.remove(0);
@@ -22,7 +22,12 @@ pub struct IfCommand {
 impl Command for IfCommand {
     fn run(&mut self, core: &mut ShellCore, _: bool) -> Result<(), ExecError> {
         for i in 0..self.if_elif_scripts.len() {
-            self.if_elif_scripts[i].exec(core)?;
+            let susp_e_option = core.suspend_e_option;
+            core.suspend_e_option = true;
+            let result = self.if_elif_scripts[i].exec(core);
+            core.suspend_e_option = susp_e_option;
+            result?;
+
             if core.db.exit_status == 0 {
                 let _ = self.then_scripts[i].exec(core);
                 return Ok(());
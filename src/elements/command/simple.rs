@@ -6,13 +6,13 @@ pub mod hash;
 pub mod parser;
 pub mod run_internal;
 
-use crate::{proc_ctrl, ShellCore};
+use crate::{proc_ctrl, signal, ShellCore};
 
 use super::{Command, Pipe, Redirect};
 use crate::elements::substitution::Substitution;
 use crate::elements::word::Word;
 use crate::error::exec::ExecError;
-use crate::utils::exit;
+use crate::utils::{self, exit, file_check};
 use nix::unistd::Pid;
 use std::sync::atomic::Ordering::Relaxed;
 
@@ -44,7 +44,8 @@ pub struct SimpleCommand {
     pub lineno: usize,
     continue_alias_check: bool,
     invalid_alias: bool,
-    command_path: String,
+    pub command_path: String,
+    pub skip_functions: bool,
 }
 
 impl Command for SimpleCommand {
@@ -55,9 +56,15 @@ impl Command for SimpleCommand {
             return Ok(None);
         }
 
-        core.db.set_param("BASH_COMMAND", &self.text, None)?;
+        if !signal::run_debug_trap(core, &self.text) {
+            return Ok(None);
+        }
+        if !core.debug_trap_running {
+            core.db.set_param("BASH_COMMAND", &self.text, None)?;
+        }
 
         self.args.clear();
+        self.command_path.clear();
         let mut words = self.words.to_vec();
         for w in words.iter_mut() {
             w.set_pipe(core); //for >()
@@ -137,26 +144,28 @@ impl SimpleCommand {
         }
 
         if self.args[0] == "command" && self.args.len() > 1 {
-            if core.subst_builtins.contains_key(&self.args[1])
-            || core.db.functions.contains_key(&self.args[1]) {
+            if core.subst_builtins.contains_key(&self.args[1]) {
                 self.args.remove(0);
             }
         }
 
         let internal = core.builtins.contains_key(&self.args[0])
                        || core.subst_builtins.contains_key(&self.args[0])
-                       || core.db.functions.contains_key(&self.args[0]);
+                       || (!self.skip_functions && core.db.functions.contains_key(&self.args[0]))
+                       || self.autocd(core);
 
         if self.force_fork
             || (!pipe.lastpipe && pipe.is_connected())
             || !internal {
-            if ! internal {
+            if ! internal && self.command_path.is_empty() {
                 self.command_path = hash::get_and_regist(self, core)?;
             }
             self.fork_exec(core, pipe)
         } else if self.args.len() == 1 && self.args[0] == "exec" {
+            /* the redirections are applied to the shell itself */
+            core.db.exit_status = 0;
             for r in self.get_redirects().iter_mut() {
-                if let Err(e) = r.connect(true, core) {
+                if let Err(e) = r.connect(false, core) {
                     e.print(core);
                     core.db.exit_status = 1;
                     break;
@@ -176,6 +185,25 @@ impl SimpleCommand {
         }
     }
 
+    /* a directory name which is not a command is given to cd */
+    fn autocd(&mut self, core: &mut ShellCore) -> bool {
+        if !core.shopts.query("autocd")
+        || !core.db.flags.contains('i')
+        || !core.builtins.contains_key("cd")
+        || !file_check::is_dir(&self.args[0]) {
+            return false;
+        }
+
+        if !self.args[0].contains('/')
+        && !utils::get_command_path(&self.args[0], core).is_empty() {
+            return false;
+        }
+
+        eprintln!("cd -- {}", &self.args[0]);
+        self.args.splice(0..0, ["cd".to_string(), "--".to_string()]);
+        true
+    }
+
     fn check_sigint(core: &mut ShellCore) -> Result<(), ExecError> {
         if core.sigint.load(Relaxed) {
             core.db.exit_status = 130;
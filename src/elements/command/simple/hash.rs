@@ -3,7 +3,7 @@
 
 use super::SimpleCommand;
 use crate::elements::command::ExecError;
-use crate::{utils, ShellCore};
+use crate::{file_check, utils, ShellCore};
 
 pub fn get_and_regist(com: &mut SimpleCommand, core: &mut ShellCore) -> Result<String, ExecError> {
     if ["/", "./", "../"]
@@ -15,6 +15,14 @@ pub fn get_and_regist(com: &mut SimpleCommand, core: &mut ShellCore) -> Result<S
 
     let mut path = core.db.get_elem("BASH_CMDS", &com.args[0])?;
 
+    /* a cached path which is no longer executable is searched again */
+    if !path.is_empty()
+        && (core.shopts.query("checkhash") || core.options.query("posix"))
+        && !file_check::is_executable(&path)
+    {
+        path.clear();
+    }
+
     if path.is_empty() {
         path = resolve_path(&com.args[0], core)?;
     }
@@ -23,23 +31,29 @@ pub fn get_and_regist(com: &mut SimpleCommand, core: &mut ShellCore) -> Result<S
     Ok(path)
 }
 
-fn resolve_path(arg: &str, core: &mut ShellCore) -> Result<String, ExecError> {
+pub fn resolve_path(arg: &str, core: &mut ShellCore) -> Result<String, ExecError> {
     let path = utils::get_command_path(arg, core);
     if path.is_empty() {
         return Ok(path);
     }
 
+    regist(arg, &path, core)?;
+    Ok(path)
+}
+
+pub fn regist(arg: &str, path: &str, core: &mut ShellCore) -> Result<(), ExecError> {
     let restricted = core.db.flags.contains('r');
     core.db.flags.retain(|f| f != 'r');
-    core.db.set_assoc_elem("BASH_CMDS", arg, &path, None)?;
+    let result = core.db.set_assoc_elem("BASH_CMDS", arg, path, Some(0));
     if restricted {
         core.db.flags.push('r');
     }
 
-    Ok(path)
+    core.db.hash_counter.insert(arg.to_string(), 0);
+    result
 }
 
-fn count_up(arg: &str, core: &mut ShellCore) {
+pub fn count_up(arg: &str, core: &mut ShellCore) {
     match core.db.hash_counter.get_mut(arg) {
         Some(v) => *v += 1,
         None => {
@@ -78,7 +78,10 @@ impl SimpleCommand {
 
     pub fn parse(feeder: &mut Feeder, core: &mut ShellCore)
     -> Result<Option<Box<dyn Command>>, ParseError> {
-        let mut ans = Self::default();
+        let mut ans = Self {
+            lineno: feeder.lineno,
+            ..Default::default()
+        };
         feeder.set_backup();
 
         while command::eat_redirects(feeder, core, &mut ans.redirects, &mut ans.text)?
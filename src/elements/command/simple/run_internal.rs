@@ -8,7 +8,7 @@ use crate::error::exec::ExecError;
 use crate::{Feeder, ShellCore};
 
 pub fn run(com: &mut SimpleCommand, core: &mut ShellCore) -> Result<bool, ExecError> {
-    let ans = run_function(&mut com.args, core)
+    let ans = (!com.skip_functions && run_function(&mut com.args, core))
         || run_substitution_builtin(com, core)?
         || run_builtin(com, core)?;
     Ok(ans)
@@ -7,7 +7,7 @@ use crate::elements::expr::conditional::elem::CondElem;
 use crate::elements::expr::conditional::ConditionalExpr;
 use crate::error::exec::ExecError;
 use crate::error::parse::ParseError;
-use crate::{Feeder, ShellCore};
+use crate::{signal, Feeder, ShellCore};
 
 #[derive(Debug, Clone, Default)]
 pub struct TestCommand {
@@ -20,6 +20,9 @@ pub struct TestCommand {
 
 impl Command for TestCommand {
     fn run(&mut self, core: &mut ShellCore, _: bool) -> Result<(), ExecError> {
+        if !signal::run_debug_trap(core, self.text.trim()) {
+            return Ok(());
+        }
         if core.db.flags.contains('x') {
             let ps4 = core.get_ps4();
             eprint!("\r{} [[ ", &ps4);
@@ -200,31 +200,33 @@ impl ConditionalExpr {
             Err(e) => return Err(e),
         };
 
-        if op == "-o" || op == "-v" || op == "-z" || op == "-n" {
-            let ans = match op {
-                "-o" => core.options.query(&operand),
-                "-v" => {
-                    if env::var(&operand).is_ok() {
-                        true
+        let ans = Self::unary_test(op, &operand, core)?;
+        stack.push(CondElem::Ans(ans));
+        Ok(())
+    }
+
+    /* also used by the test builtin */
+    pub fn unary_test(op: &str, operand: &str, core: &mut ShellCore) -> Result<bool, ExecError> {
+        let ans = match op {
+            "-o" => core.options.query(operand),
+            "-v" => {
+                if env::var(operand).is_ok() {
+                    true
+                } else {
+                    let mut f = Feeder::new(operand);
+                    if let Some(v) = Variable::parse(&mut f, core)? {
+                        v.exist(core)?
                     } else {
-                        let mut f = Feeder::new(&operand);
-                        if let Some(v) = Variable::parse(&mut f, core)? {
-                            v.exist(core)?
-                        } else {
-                            false
-                        }
+                        false
                     }
                 }
-                "-z" => operand.is_empty(),
-                "-n" => !operand.is_empty(),
-                _ => false,
-            };
-
-            stack.push(CondElem::Ans(ans));
-            return Ok(());
-        }
-
-        Self::unary_file_check(op, &operand, stack)
+            }
+            "-R" => core.db.exist_nameref(operand),
+            "-z" => operand.is_empty(),
+            "-n" => !operand.is_empty(),
+            _ => return Self::file_test(op, operand),
+        };
+        Ok(ans)
     }
 
     fn regex_operation(stack: &mut Vec<CondElem>, core: &mut ShellCore) -> Result<(), ExecError> {
@@ -365,7 +367,7 @@ impl ConditionalExpr {
         Ok(())
     }
 
-    fn unary_file_check(op: &str, s: &str, stack: &mut Vec<CondElem>) -> Result<(), ExecError> {
+    pub fn file_test(op: &str, s: &str) -> Result<bool, ExecError> {
         let result = match op {
             "-a" | "-e" => file_check::exists(s),
             "-d" => file_check::is_dir(s),
@@ -381,8 +383,7 @@ impl ConditionalExpr {
             _ => return Err(ExecError::Other("unsupported option".to_string())),
         };
 
-        stack.push(CondElem::Ans(result));
-        Ok(())
+        Ok(result)
     }
 
     fn rev_polish_op(elem: &CondElem, stack: &mut Vec<CondElem>, ans: &mut Vec<CondElem>) -> bool {
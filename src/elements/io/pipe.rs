@@ -17,6 +17,7 @@ pub struct Pipe {
     pub lastpipe_backup: RawFd,
     pub proc_sub_recv: RawFd,
     pub proc_sub_send: RawFd,
+    pub coproc_fds: Vec<RawFd>, // closed in the child
 }
 
 impl Pipe {
@@ -31,6 +32,7 @@ impl Pipe {
             lastpipe_backup: -1,
             proc_sub_recv: -1,
             proc_sub_send: -1,
+            coproc_fds: vec![],
         }
     }
 
@@ -87,6 +89,9 @@ impl Pipe {
         }
 
         core.fds.close(self.recv);
+        for fd in &self.coproc_fds {
+            core.fds.close(*fd);
+        }
         core.fds.replace(self.send, 1)?;
         core.fds.replace(self.prev, 0)?;
 
@@ -9,6 +9,7 @@ use crate::error::exec::ExecError;
 use crate::error::parse::ParseError;
 use crate::utils::{exit, file_check};
 use crate::{error, Feeder, ShellCore};
+use nix::errno::Errno;
 use nix::unistd;
 use nix::unistd::ForkResult;
 use std::fs::{File, OpenOptions};
@@ -84,6 +85,16 @@ impl Redirect {
         }
     }
 
+    fn close_left(&mut self, default_fd: RawFd, restore: bool, core: &mut ShellCore) -> Result<(), ExecError> {
+        self.set_left_fd(default_fd);
+        if restore {
+            self.left_backup = core.fds.backup(self.left_fd);
+        }
+
+        core.fds.close(self.left_fd);
+        Ok(())
+    }
+
     fn connect_to_file(
         &mut self,
         file_open_result: Result<File, Error>,
@@ -108,8 +119,9 @@ impl Redirect {
                 }
                 Ok(())
             }
-            _ => {
-                let msg = format!("{}: {}", &self.right.text, Error::last_os_error().kind());
+            Err(e) => {
+                let errno = Errno::from_raw(e.raw_os_error().unwrap_or(0));
+                let msg = format!("{}: {}", &self.right.text, errno.desc());
                 Err(ExecError::Other(msg))
             }
         }
@@ -128,9 +140,7 @@ impl Redirect {
 
     fn redirect_output_fd(&mut self, restore: bool, core: &mut ShellCore) -> Result<(), ExecError> {
         if self.right.text == "-" {
-            self.set_left_fd(1);
-            core.fds.close(self.left_fd);
-            return Ok(());
+            return self.close_left(1, restore, core);
         }
 
         let right_fd = match self.right.text.parse::<RawFd>() {
@@ -149,9 +159,7 @@ impl Redirect {
     fn redirect_input_fd(&mut self, restore: bool,
                          core: &mut ShellCore) -> Result<(), ExecError> {
         if self.right.text == "-" {
-            self.set_left_fd(0);
-            core.fds.close(self.left_fd);
-            return Ok(());
+            return self.close_left(0, restore, core);
         }
 
         let right_fd = match self.right.text.parse::<RawFd>() {
@@ -194,11 +202,11 @@ impl Redirect {
         core: &mut ShellCore,
         restore: bool,
     ) -> Result<(), ExecError> {
-        self.left_fd = 0;
+        self.set_left_fd(0);
         let (recv, send) = core.fds.pipe();
 
         if restore {
-            self.left_backup = core.fds.backup(0);
+            self.left_backup = core.fds.backup(self.left_fd);
         }
 
         let right = self.right.make_unquoted_word().unwrap_or("".to_string());
@@ -220,7 +228,7 @@ impl Redirect {
             }
             ForkResult::Parent { child: _ } => {
                 core.fds.close(send);
-                core.fds.replace(recv, 0)?;
+                core.fds.replace(recv, self.left_fd)?;
             }
         }
         Ok(())
@@ -231,11 +239,11 @@ impl Redirect {
         core: &mut ShellCore,
         restore: bool,
     ) -> Result<(), ExecError> {
-        self.left_fd = 0;
+        self.set_left_fd(0);
         let (recv, send) = core.fds.pipe();
 
         if restore {
-            self.left_backup = core.fds.backup(0);
+            self.left_backup = core.fds.backup(self.left_fd);
         }
 
         let text = self.right.eval_as_herestring(core)?;
@@ -251,7 +259,7 @@ impl Redirect {
             }
             ForkResult::Parent { child: _ } => {
                 core.fds.close(send);
-                core.fds.replace(recv, 0)?;
+                core.fds.replace(recv, self.left_fd)?;
             }
         }
         Ok(())
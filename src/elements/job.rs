@@ -28,7 +28,7 @@ impl Job {
         };
 
         match bg {
-            true => self.exec_bg(core, pgid),
+            true => self.exec_bg(core, pgid)?,
             false => self.exec_fg(core, pgid)?,
         }
         Ok(())
@@ -90,7 +90,12 @@ impl Job {
         }
     }
 
-    fn exec_bg(&mut self, core: &mut ShellCore, pgid: Pid) {
+    fn exec_bg(&mut self, core: &mut ShellCore, pgid: Pid) -> Result<(), ExecError> {
+        if !core.wait_job_slot() {
+            core.db.exit_status = 130;
+            return Err(ExecError::Interrupted);
+        }
+
         let backup = core.tty_fd.clone();//core.tty_fd.as_ref().map(|fd| fd.try_clone().unwrap());
         core.tty_fd = None;
 
@@ -104,7 +109,7 @@ impl Job {
                 Ok(pid) => vec![pid],
                 Err(e) => {
                     e.print(core);
-                    return;
+                    return Ok(());
                 }
             }
         };
@@ -131,6 +136,7 @@ impl Job {
         core.job_table.push(entry);
 
         core.tty_fd = backup;
+        Ok(())
     }
 
     fn exec_fork_bg(&mut self, core: &mut ShellCore, pgid: Pid) -> Result<Option<Pid>, ExecError> {
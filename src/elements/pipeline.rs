@@ -8,6 +8,7 @@ use crate::error::exec::ExecError;
 use crate::error::parse::ParseError;
 use crate::{Feeder, ShellCore};
 use nix::sys::resource;
+use nix::sys::time::TimeVal;
 use nix::time;
 use nix::time::ClockId;
 use nix::unistd::Pid;
@@ -19,6 +20,7 @@ pub struct Pipeline {
     pub text: String,
     exclamation: bool,
     pub time: bool,
+    time_posix: bool,
 }
 
 impl Pipeline {
@@ -100,6 +102,17 @@ impl Pipeline {
             return;
         }
 
+        core.measured_time.posix_format = self.time_posix;
+        core.measured_time.null_command = self.commands.is_empty();
+
+        /* `time' alone reports the shell and its children since the start */
+        if self.commands.is_empty() {
+            core.measured_time.user = TimeVal::new(0, 0);
+            core.measured_time.sys = TimeVal::new(0, 0);
+            core.measured_time.real = core.measured_time.shell_start;
+            return;
+        }
+
         let self_usage = resource::getrusage(resource::UsageWho::RUSAGE_SELF).unwrap();
         let children_usage = resource::getrusage(resource::UsageWho::RUSAGE_CHILDREN).unwrap();
 
@@ -147,15 +160,43 @@ impl Pipeline {
         true
     }
 
+    fn is_word_end(feeder: &mut Feeder, word: &str, ends: &[&str]) -> bool {
+        if feeder.len() == word.len() {
+            return feeder.starts_with(word);
+        }
+        ends.iter().any(|e| feeder.starts_with(&(word.to_string() + e)))
+    }
+
+    fn eat_time_option(
+        feeder: &mut Feeder,
+        ans: &mut Self,
+        core: &mut ShellCore,
+        opt: &str,
+    ) -> bool {
+        if !Self::is_word_end(feeder, opt, &[" ", "\t", "\n", ";"]) {
+            return false;
+        }
+
+        ans.text += &feeder.consume(opt.len());
+        let blank_len = feeder.scanner_blank(core);
+        ans.text += &feeder.consume(blank_len);
+        true
+    }
+
     fn eat_time(feeder: &mut Feeder, ans: &mut Self, core: &mut ShellCore) -> bool {
-        match feeder.starts_with("time ") || feeder.starts_with("time\t") {
-            true => ans.text += &feeder.consume(4),
-            false => return false,
+        if !Self::is_word_end(feeder, "time", &[" ", "\t", "\n", ";", "&"]) {
+            return false;
         }
 
+        ans.text += &feeder.consume(4);
         ans.time = true;
         let blank_len = feeder.scanner_blank(core);
         ans.text += &feeder.consume(blank_len);
+
+        if Self::eat_time_option(feeder, ans, core, "-p") {
+            ans.time_posix = true;
+        }
+        Self::eat_time_option(feeder, ans, core, "--");
         true
     }
 
@@ -7,12 +7,11 @@ use crate::elements::subword::Subword;
 use crate::elements::Pipe;
 use crate::error::exec::ExecError;
 use crate::error::parse::ParseError;
-use crate::{proc_ctrl, Feeder, ShellCore};
+use crate::{proc_ctrl, signal, Feeder, ShellCore};
 use nix::unistd;
-use std::io::{BufRead, BufReader, Error};
+use std::io::{ErrorKind, Read};
 use std::os::fd::RawFd;
 use std::sync::atomic::Ordering::Relaxed;
-use std::{thread, time};
 
 #[derive(Debug, Clone, Default)]
 pub struct CommandSubstitution {
@@ -33,7 +32,10 @@ impl Subword for CommandSubstitution {
         pipe.set(-1, unistd::getpgrp(), core);
         let pid = self.command.exec(core, &mut pipe)?;
         let result = self.read(pipe.recv, core);
+        let susp_e_option = core.suspend_e_option;
+        core.suspend_e_option = true;
         proc_ctrl::wait_pipeline(core, vec![pid], false, false);
+        core.suspend_e_option = susp_e_option;
         result?;
         self.text = self.text.trim_end_matches("\n").to_string();
         Ok(())
@@ -41,39 +43,27 @@ impl Subword for CommandSubstitution {
 }
 
 impl CommandSubstitution {
-    fn set_line(&mut self, line: Result<String, Error>) -> bool {
-        if let Ok(ln) = line {
-            self.text.push_str(&ln);
-            self.text.push('\n');
-            return true;
-        }
-        false
-    }
-
-    fn interrupted(&mut self, count: usize, core: &mut ShellCore) -> Result<(), ExecError> {
-        if count % 100 == 99 {
-            //To receive Ctrl+C
-            thread::sleep(time::Duration::from_millis(1));
-        }
-        match core.sigint.load(Relaxed) {
-            true => Err(ExecError::Interrupted),
-            false => Ok(()),
-        }
-    }
-
     fn read(&mut self, fd: RawFd, core: &mut ShellCore) -> Result<(), ExecError> {
-        //let f = unsafe { File::from_raw_fd(fd) };
-        let f = core.fds.get_file(fd);
-        let reader = BufReader::new(f);
-        self.text.clear();
-        for (i, line) in reader.lines().enumerate() {
-            self.interrupted(i, core)?;
-            if !self.set_line(line) {
-                break;
+        let mut f = core.fds.get_file(fd);
+        let mut buf = [0u8; 4096];
+        let mut output = vec![];
+        loop {
+            if !signal::wait_event(Some(fd)) {
+                match core.sigint.load(Relaxed) {
+                    true => return Err(ExecError::Interrupted),
+                    false => continue,
+                }
+            }
+
+            match f.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => output.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => break,
             }
         }
 
-        self.text.pop();
+        self.text = String::from_utf8_lossy(&output).to_string();
         Ok(())
     }
 
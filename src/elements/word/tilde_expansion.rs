@@ -1,6 +1,7 @@
 //SPDX-FileCopyrightText: 2024 Ryuichi Ueda ryuichiueda@gmail.com
 //SPDX-License-Identifier: BSD-3-Clause
 
+use crate::core::builtins::dirs;
 use crate::elements::word::Word;
 use crate::error::exec::ExecError;
 use crate::ShellCore;
@@ -104,6 +105,9 @@ fn get_value(text: &str, core: &mut ShellCore) -> Result<String, ExecError> {
         "" => "HOME",
         "+" => "PWD",
         "-" => "OLDPWD",
+        _ if text.trim_start_matches(['+', '-']).parse::<usize>().is_ok() => {
+            return Ok(dirs::get_entry(core, text).unwrap_or_default());
+        }
         _ => return Ok(get_home_dir(text)),
     };
 
@@ -2,11 +2,11 @@
 //SPDX-License-Identifier: BSD-3-Clause
 
 mod scanner;
-mod terminal;
+pub mod terminal;
 
 use crate::error::input::InputError;
 use crate::error::parse::ParseError;
-use crate::{utils, ShellCore};
+use crate::{file_check, utils, ShellCore};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Lines};
 use std::sync::atomic::Ordering::Relaxed;
@@ -132,7 +132,7 @@ impl Feeder {
         }
 
         let line = match core.db.flags.contains('i') && self.script_lines.is_none() {
-            true => terminal::read_line(core, "PS2"),
+            true => self.read_line_interactive(core, "PS2"),
             false => self.read_script(),
         };
 
@@ -158,13 +158,22 @@ impl Feeder {
 
     pub fn feed_line(&mut self, core: &mut ShellCore) -> Result<(), InputError> {
         let line = match core.db.flags.contains('i') && self.script_lines.is_none() {
-            true => terminal::read_line(core, "PS1"),
+            true => self.read_line_interactive(core, "PS1"),
             false => self.read_script(),
         };
 
         line.map(|ln| self.add_line(ln, core))
     }
 
+    fn read_line_interactive(&mut self, core: &mut ShellCore, ps: &str) -> Result<String, InputError> {
+        if file_check::is_tty(0) {
+            return terminal::read_line(core, ps);
+        }
+
+        eprint!("{}", terminal::make_prompt(core, ps));
+        self.read_script()
+    }
+
     pub fn add_line(&mut self, line: String, core: &mut ShellCore) {
         if core.db.flags.contains('v') {
             eprint!("{}", &line);
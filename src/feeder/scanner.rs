@@ -401,7 +401,7 @@ impl Feeder {
         self.backslash_check_and_feed(vec!["-"], core);
 
         if let Some(c) = self.remaining.chars().nth(1) {
-            match "abcdefghknoprstuvwxzGLNOS".contains(c) {
+            match "abcdefghknoprstuvwxzGLNORS".contains(c) {
                 true => return 2,
                 false => return 0,
             }
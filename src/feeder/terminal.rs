@@ -6,12 +6,13 @@ mod key;
 
 use crate::error::input::InputError;
 use crate::utils::{arg, file};
-use crate::{file_check, ShellCore};
+use crate::{file_check, signal, ShellCore};
 use nix::unistd;
 use nix::unistd::User;
 use std::fs::File;
 use std::io;
-use std::io::{Stdout, Write};
+use std::io::Write;
+use std::os::fd::{AsFd, BorrowedFd};
 use std::path::Path;
 use std::sync::atomic::Ordering::Relaxed;
 use termion::cursor::DetectCursorPos;
@@ -23,7 +24,7 @@ use unicode_width::UnicodeWidthChar;
 
 struct Terminal {
     prompt: String,
-    stdout: RawTerminal<Stdout>,
+    out: RawTerminal<File>,
     prompt_row: usize,
     chars: Vec<char>,
     head: usize,
@@ -88,25 +89,30 @@ impl Terminal {
         let ansi_on_prompt = oct_to_hex_in_str(&raw_prompt);
 
         let replaced_prompt = Self::make_prompt_string(&ansi_on_prompt);
-        let prompt = replaced_prompt
-            .replace("\\[", "")
-            .replace("\\]", "")
-            .to_string();
-        print!("{prompt}");
+        Self::with_prompt(&replaced_prompt, io::stdout().as_fd())
+    }
+
+    /* the line is edited on a duplicate of FD so that read -e can use stderr */
+    fn with_prompt(replaced_prompt: &str, fd: BorrowedFd) -> Self {
+        let prompt = remove_brackets(replaced_prompt);
         io::stdout().flush().unwrap();
 
-        let mut sout = io::stdout().into_raw_mode().unwrap();
-        let row = sout.cursor_pos().unwrap_or((1, 1)).1;
+        let mut out = File::from(fd.try_clone_to_owned().unwrap());
+        write!(out, "{prompt}").unwrap();
+
+        let mut out = out.into_raw_mode().unwrap();
+        let row = out.cursor_pos().unwrap_or((1, 1)).1;
+        let size = Terminal::size(&out);
 
         Terminal {
             prompt: prompt.to_string(),
-            stdout: sout,
+            out,
             prompt_row: row as usize,
             chars: prompt.chars().collect(),
             head: prompt.chars().count(),
             hist_ptr: 0,
-            size: Terminal::size(),
-            prompt_width_map: Self::make_width_map(&replaced_prompt),
+            size,
+            prompt_width_map: Self::make_width_map(replaced_prompt),
             prev_key: event::Key::Char('a'),
             tab_num: 0,
             completion_candidate: String::new(),
@@ -192,11 +198,11 @@ impl Terminal {
     }
 
     fn write(&mut self, s: &str) {
-        write!(self.stdout, "{s}").unwrap();
+        write!(self.out, "{s}").unwrap();
     }
 
     fn flush(&mut self) {
-        self.stdout.flush().unwrap();
+        self.out.flush().unwrap();
     }
 
     fn char_width(&self, c: &char, pos: usize) -> usize {
@@ -207,8 +213,8 @@ impl Terminal {
         UnicodeWidthChar::width(*c).unwrap_or(0)
     }
 
-    fn size() -> (usize, usize) {
-        let (c, r) = termion::terminal_size().unwrap();
+    fn size(out: &File) -> (usize, usize) {
+        let (c, r) = termion::terminal_size_fd(out).unwrap();
         (c as usize, r as usize)
     }
 
@@ -223,7 +229,7 @@ impl Terminal {
     }
 
     fn head_to_cursor_pos(&self, head: usize, y_origin: usize) -> (usize, usize) {
-        let col = Terminal::size().0;
+        let col = Terminal::size(&self.out).0;
         let (mut x, mut y) = (0, y_origin);
 
         for (i, c) in self.chars[..head].iter().enumerate() {
@@ -247,7 +253,7 @@ impl Terminal {
 
     fn goto(&mut self, head: usize) {
         let pos = self.head_to_cursor_pos(head, self.prompt_row);
-        let size = Terminal::size();
+        let size = Terminal::size(&self.out);
 
         let x: u16 = std::cmp::min(size.0, pos.0).try_into().unwrap();
         let y: u16 = std::cmp::min(size.1, pos.1).try_into().unwrap();
@@ -287,6 +293,20 @@ impl Terminal {
         self.rewrite(true);
     }
 
+    fn notify_job_changes(&mut self, core: &mut ShellCore) {
+        self.goto(self.chars.len());
+        self.write("\r\n");
+        self.flush();
+
+        let _ = self.out.suspend_raw_mode();
+        core.jobtable_print_status_change();
+        io::stdout().flush().unwrap();
+        let _ = self.out.activate_raw_mode();
+
+        self.prompt_row = self.out.cursor_pos().unwrap_or((1, 1)).1 as usize;
+        self.rewrite(false);
+    }
+
     pub fn get_string(&self, from: usize) -> String {
         self.chars[from..].iter().collect()
     }
@@ -323,7 +343,7 @@ impl Terminal {
 
     pub fn check_scroll(&mut self) {
         let extra_lines = self.head_to_cursor_pos(self.chars.len(), 0).1;
-        let row = Terminal::size().1;
+        let row = Terminal::size(&self.out).1;
 
         if self.prompt_row + extra_lines > row {
             let ans = row as isize - extra_lines as isize;
@@ -333,13 +353,13 @@ impl Terminal {
 
     pub fn check_terminal_size(&mut self /*, prev_size: &mut (usize, usize)*/) {
         //if *prev_size == Terminal::size() {
-        if self.size == Terminal::size() {
+        if self.size == Terminal::size(&self.out) {
             return;
         }
 
         let from_under = self.size.1 as isize - self.prompt_row as isize;
         //*prev_size = Terminal::size();
-        self.size = Terminal::size();
+        self.size = Terminal::size(&self.out);
 
         let cur_row = self.size.1 as isize - from_under;
         self.prompt_row = std::cmp::max(cur_row, 1) as usize;
@@ -371,7 +391,7 @@ impl Terminal {
     }
 
     pub fn cloop(&mut self) {
-        print!("\x07");
+        self.write("\x07");
         self.flush();
     }
 
@@ -390,28 +410,79 @@ impl Terminal {
     }
 }
 
+fn remove_brackets(prompt: &str) -> String {
+    prompt.replace("\\[", "").replace("\\]", "")
+}
+
+/* for interactive shells whose stdin is not a terminal */
+pub fn make_prompt(core: &mut ShellCore, ps: &str) -> String {
+    let raw_prompt = core.db.get_param(ps).unwrap_or_default();
+    let ansi_on_prompt = oct_to_hex_in_str(&raw_prompt);
+    remove_brackets(&Terminal::make_prompt_string(&ansi_on_prompt))
+}
+
 fn signal_check(core: &mut ShellCore, term: &mut Terminal) -> Result<bool, InputError> {
-    if core.sigint.load(Relaxed) || core.trapped.iter_mut().any(|t| t.0.load(Relaxed)) {
+    if core.sigint.load(Relaxed) || signal::is_trapped() {
         term.write("\r\n");
         return Err(InputError::Interrupt);
     }
     Ok(true)
 }
 
+/* stdin that gives up reading when a signal arrives */
+struct SignalAwareStdin;
+
+impl io::Read for SignalAwareStdin {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !signal::wait_event(Some(0)) {
+            return Err(io::Error::other("signal arrived"));
+        }
+        unistd::read(io::stdin(), buf).map_err(io::Error::from)
+    }
+}
+
 pub fn read_line(core: &mut ShellCore, prompt: &str) -> Result<String, InputError> {
-    let mut term = Terminal::new(core, prompt);
+    let term = Terminal::new(core, prompt);
+    let ans = edit(core, term)?;
+    core.history[0] = ans.trim_end().to_string();
+    Ok(ans)
+}
+
+/* for read -e: the line is not recorded in the history
+ * and is edited on stderr like the prompt of read -p */
+pub fn read_line_with_text(
+    core: &mut ShellCore,
+    prompt: &str,
+    text: &str,
+) -> Result<String, InputError> {
+    let mut term = Terminal::with_prompt(prompt, io::stderr().as_fd());
+    text.chars().for_each(|c| term.insert(c));
+    let ans = edit(core, term)?;
+    core.history.remove(0);
+    Ok(ans)
+}
+
+fn edit(core: &mut ShellCore, mut term: Terminal) -> Result<String, InputError> {
     signal_check(core, &mut term)?;
 
     core.history.insert(0, String::new());
 
-    for c in io::stdin().keys() {
-        let c = c.unwrap();
-
+    for c in SignalAwareStdin.keys() {
         if let Err(e) = signal_check(core, &mut term) {
             core.history.remove(0);
             return Err(e);
         }
 
+        let c = match c {
+            Ok(c) => c,
+            Err(_) => {
+                if core.options.query("notify") && core.jobtable_reap() {
+                    term.notify_job_changes(core);
+                }
+                continue;
+            }
+        };
+
         term.check_terminal_size();
         match key::action(core, &mut term, &c) {
             Ok(true) => break,
@@ -426,9 +497,7 @@ pub fn read_line(core: &mut ShellCore, prompt: &str) -> Result<String, InputErro
         term.check_scroll();
     }
 
-    let ans = term.get_string(term.prompt.chars().count());
-    core.history[0] = ans.trim_end().to_string();
-    Ok(ans)
+    Ok(term.get_string(term.prompt.chars().count()))
 }
 
 /*TODO: The following version uses async_stdin and enables
@@ -7,7 +7,7 @@ use crate::elements::command::simple::SimpleCommand;
 use crate::elements::io::pipe::Pipe;
 use crate::error::exec::ExecError;
 use crate::feeder::terminal::Terminal;
-use crate::utils::arg;
+use crate::utils::{arg, directory};
 use crate::{file_check, utils, Feeder, ShellCore};
 use unicode_width::UnicodeWidthStr;
 
@@ -192,9 +192,13 @@ impl Terminal {
 
         let com = core.db.get_elem("COMP_WORDS", "0")?;
 
-        let (tilde_prefix, tilde_path, last_tilde_expanded) =
+        let (tilde_prefix, tilde_path, mut last_tilde_expanded) =
             Self::set_tilde_transform(&last, core);
 
+        if pos != "0" && core.shopts.query("dirspell") {
+            Self::correct_dir_spelling(&mut last_tilde_expanded);
+        }
+
         let args = vec![
             "".to_string(),
             "".to_string(),
@@ -324,7 +328,7 @@ impl Terminal {
                 };
                 self.print_an_entry(&entry);
             }
-            print!("\r\n");
+            self.write("\r\n");
         }
 
         let (cur_col, cur_row) = self.head_to_cursor_pos(self.head, self.prompt_row);
@@ -334,7 +338,7 @@ impl Terminal {
             true => {
                 let back_row = std::cmp::max(cur_row as i16 - row_num as i16, 1);
                 self.write(&termion::cursor::Goto(cur_col as u16, back_row as u16).to_string());
-                print!("\x1b[1A");
+                self.write("\x1b[1A");
                 self.flush();
             }
             false => self.rewrite(false),
@@ -354,10 +358,10 @@ impl Terminal {
 
         let s = String::from_utf8(vec![b' '; space_num]).unwrap();
         if entry.pointed {
-            print!("\x1b[01;7m{}{}\x1b[00m", &cand, &s);
+            self.write(&format!("\x1b[01;7m{}{}\x1b[00m", &cand, &s));
             self.completion_candidate = cand;
         } else {
-            print!("{}{}", &cand, &s);
+            self.write(&format!("{}{}", &cand, &s));
         }
     }
 
@@ -385,6 +389,21 @@ impl Terminal {
         self.rewrite(true);
     }
 
+    /* the directory part of the word is corrected when it doesn't exist */
+    fn correct_dir_spelling(word: &mut String) {
+        let (dir, file) = match word.rfind('/') {
+            Some(n) => word.split_at(n),
+            None => return,
+        };
+
+        if dir.is_empty() || file_check::is_dir(dir) {
+            return;
+        }
+        if let Some(fixed) = directory::correct_spelling(dir) {
+            *word = fixed + file;
+        }
+    }
+
     fn set_tilde_transform(last: &str, core: &mut ShellCore) -> (String, String, String) {
         let tilde_prefix;
         let tilde_path;
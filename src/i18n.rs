@@ -234,23 +234,39 @@ pub fn load_fluent_bundle() -> Option<FluentBundle<FluentResource>> {
     let mut bundle = FluentBundle::new(vec![langid]);
     bundle.add_resource(res).ok()?;
 
+    /* messages not translated yet are taken from English */
+    let en = LANGS.iter().find(|e| e.code == "en" && e.code != entry.code);
+    if let Some(res) = en.and_then(|e| FluentResource::try_new(e.ftl.to_string()).ok()) {
+        let _ = bundle.add_resource(res); // errors only report the translated ones
+    }
+
     Some(bundle)
 }
 
-pub fn fl(key: &str) -> String {
+fn format(key: &str, attr: Option<&str>) -> Option<String> {
     FLUENT_BUNDLE.with(|cell| {
         let bundle =
             cell.get_or_init(|| load_fluent_bundle().expect("Could not load translation bundle"));
 
+        let msg = bundle.get_message(key)?;
+        let pattern = match attr {
+            Some(a) => msg.get_attribute(a)?.value(),
+            None => msg.value()?,
+        };
+
         let mut errors = vec![];
-        bundle
-            .get_message(key)
-            .and_then(|msg| msg.value())
-            .map(|pattern| {
-                bundle
-                    .format_pattern(pattern, None, &mut errors)
-                    .to_string()
-            })
-            .unwrap_or_else(|| format!("{{{key}}}"))
+        Some(bundle.format_pattern(pattern, None, &mut errors).to_string())
     })
 }
+
+pub fn fl(key: &str) -> String {
+    format(key, None).unwrap_or_else(|| format!("{{{key}}}"))
+}
+
+pub fn fl_opt(key: &str) -> Option<String> {
+    format(key, None)
+}
+
+pub fn fl_attr(key: &str, attr: &str) -> Option<String> {
+    format(key, Some(attr))
+}
@@ -19,6 +19,7 @@ use std::{env, process};
 use crate::core::builtins::source;
 use crate::core::{builtins, ShellCore};
 use crate::elements::script::Script;
+use crate::elements::word::{Word, WordMode};
 use crate::feeder::Feeder;
 use crate::i18n::FLUENT_BUNDLE;
 use builtins::option;
@@ -42,6 +43,11 @@ fn main() {
 
     let command = args.first().cloned().unwrap_or_else(|| "sush".to_string());
     let script_parts = consume_file_and_subsequents(&mut args);
+    let startup = StartupFiles::consume(&mut args);
+    let login = consume_login_option(&mut args, &command);
+    let posix = consume_posix_option(&mut args, &command);
+    let s_opt = arg::consume_arg("-s", &mut args);
+    let i_opt = arg::consume_arg("-i", &mut args);
 
     let mut c_opt = false;
     if let Some(opt) = args.last() {
@@ -65,22 +71,26 @@ fn main() {
         cell.set(bundle).ok();
     });
 
+    let _ = core.shopts.set("login_shell", login);
+    let _ = core.options.set("posix", posix);
+    if i_opt {
+        core.db.flags += "i";
+    }
     set_o_options(&mut args, &mut core);
+    set_shopt_options(&mut args, &mut core);
     set_short_options(&mut args, &mut core);
 
     if !c_opt {
-        set_parameters(script_parts, &mut core, &command);
+        set_parameters(script_parts, &mut core, &command, s_opt);
     } else {
         main_c_option::set_parameters(&script_parts, &mut core, &args[0]);
-        main_c_option::run_and_exit(&args, &script_parts, &mut core);
+        main_c_option::run_and_exit(&args, &script_parts, &startup, &mut core);
     }
 
     let _ = core.configure();
-    signal::run_signal_check(&mut core);
+    signal::init_dispatcher(&mut core);
 
-    if core.script_name == "-" {
-        read_rc_file(&mut core);
-    }
+    read_startup_files(&mut core, &startup);
     main_loop(&mut core, &command);
 }
 
@@ -89,6 +99,7 @@ fn main() {
 fn consume_file_and_subsequents(args: &mut Vec<String>) -> Vec<String> {
     let mut skip = false;
     let mut pos = None;
+    let mut end_of_options = false;
 
     for (i, a) in args.iter().enumerate().skip(1) {
         if skip {
@@ -96,11 +107,17 @@ fn consume_file_and_subsequents(args: &mut Vec<String>) -> Vec<String> {
             continue;
         }
 
-        if a.starts_with("-o") || a.starts_with("+o") {
+        if arg::takes_next_arg(a) {
             skip = true;
             continue;
         }
 
+        if a == "--" || a == "-" {
+            pos = Some(i);
+            end_of_options = true;
+            break;
+        }
+
         if a.starts_with('-') || a.starts_with('+') {
             continue;
         }
@@ -113,7 +130,51 @@ fn consume_file_and_subsequents(args: &mut Vec<String>) -> Vec<String> {
         return vec![];
     }
 
-    args.split_off(pos.unwrap())
+    let mut ans = args.split_off(pos.unwrap());
+    if end_of_options {
+        ans.remove(0);
+    }
+    ans
+}
+
+#[derive(Debug, Default)]
+pub struct StartupFiles {
+    rcfile: Option<String>,
+    norc: bool,
+    noprofile: bool,
+}
+
+impl StartupFiles {
+    fn consume(args: &mut Vec<String>) -> Self {
+        let mut ans = Self::default();
+        loop {
+            if let Some(f) = arg::consume_with_next_arg("--rcfile", args) {
+                ans.rcfile = Some(f);
+                continue;
+            }
+            if let Some(f) = arg::consume_with_next_arg("--init-file", args) {
+                ans.rcfile = Some(f);
+                continue;
+            }
+            break;
+        }
+
+        ans.norc = arg::consume_arg("--norc", args);
+        ans.noprofile = arg::consume_arg("--noprofile", args);
+        ans
+    }
+}
+
+fn consume_posix_option(args: &mut Vec<String>, command: &str) -> bool {
+    let name = command.trim_start_matches('-');
+    let basename = name.rsplit('/').next().unwrap_or(name);
+    arg::consume_arg("--posix", args) || basename == "sh"
+}
+
+fn consume_login_option(args: &mut Vec<String>, command: &str) -> bool {
+    let l_opt = arg::consume_arg("-l", args);
+    let login_opt = arg::consume_arg("--login", args);
+    l_opt || login_opt || command.starts_with('-')
 }
 
 fn set_o_options(args: &mut Vec<String>, core: &mut ShellCore) {
@@ -139,6 +200,29 @@ fn set_o_options(args: &mut Vec<String>, core: &mut ShellCore) {
     }
 }
 
+fn set_shopt_options(args: &mut Vec<String>, core: &mut ShellCore) {
+    let mut options = vec![];
+    loop {
+        if let Some(opt) = arg::consume_with_next_arg("-O", args) {
+            options.push(("-s", opt));
+            continue;
+        }
+        if let Some(opt) = arg::consume_with_next_arg("+O", args) {
+            options.push(("-u", opt));
+            continue;
+        }
+
+        break;
+    }
+
+    for (flag, opt) in options {
+        let shopt_args = ["shopt".to_string(), flag.to_string(), opt];
+        if option::shopt(core, &shopt_args) != 0 {
+            process::exit(2);
+        }
+    }
+}
+
 fn set_short_options(args: &mut Vec<String>, core: &mut ShellCore) {
     if arg::consume_arg("-b", args) {
         core.compat_bash = true;
@@ -156,17 +240,65 @@ fn set_short_options(args: &mut Vec<String>, core: &mut ShellCore) {
     }
 }
 
-fn read_rc_file(core: &mut ShellCore) {
-    if !core.db.flags.contains("i") {
+fn read_startup_files(core: &mut ShellCore, startup: &StartupFiles) {
+    let posix = core.options.query("posix");
+
+    if core.shopts.query("login_shell") && !startup.noprofile {
+        read_profile_files(core);
+    }
+
+    if !core.db.flags.contains('i') {
+        if !posix {
+            read_env_file(core, &["SUSH_ENV", "BASH_ENV"]);
+        }
+    } else if posix {
+        read_env_file(core, &["ENV"]);
+    } else if !core.shopts.query("login_shell") && !startup.norc {
+        read_rc_file(core, &startup.rcfile);
+    }
+}
+
+fn read_env_file(core: &mut ShellCore, names: &[&str]) {
+    for name in names {
+        let value = core.db.get_param(name).unwrap_or_default();
+        if value.is_empty() {
+            continue;
+        }
+
+        let mut feeder = Feeder::new(&value);
+        let file = match Word::parse(&mut feeder, core, Some(WordMode::PermitAnyChar)) {
+            Ok(Some(w)) => match w.eval_as_value(core) {
+                Ok(f) => f,
+                Err(e) => {
+                    e.print(core);
+                    return;
+                }
+            },
+            _ => return,
+        };
+
+        if file_check::is_regular_file(&file) {
+            core.db.exit_status = source::source(core, &[".".to_string(), file]);
+        }
         return;
     }
+}
 
-    let mut dir = core.db.get_param("CARGO_MANIFEST_DIR").unwrap_or_default();
-    if dir.is_empty() {
-        dir = core.db.get_param("HOME").unwrap_or_default();
+fn read_rc_file(core: &mut ShellCore, rcfile: &Option<String>) {
+    if !core.db.flags.contains("i") {
+        return;
     }
 
-    let rc_file = dir + "/.sushrc";
+    let rc_file = match rcfile {
+        Some(f) => f.clone(),
+        None => {
+            let mut dir = core.db.get_param("CARGO_MANIFEST_DIR").unwrap_or_default();
+            if dir.is_empty() {
+                dir = core.db.get_param("HOME").unwrap_or_default();
+            }
+            dir + "/.sushrc"
+        }
+    };
 
     if file_check::is_regular_file(&rc_file) {
         core.db.exit_status = source::source(core, &[".".to_string(), rc_file]);
@@ -175,11 +307,38 @@ fn read_rc_file(core: &mut ShellCore) {
     core.continue_counter = 0; //patch for Ubuntu 25.04 bash completion
 }
 
-fn set_parameters(script_parts: Vec<String>, core: &mut ShellCore, command: &str) {
-    match script_parts.is_empty() {
+fn read_profile_files(core: &mut ShellCore) {
+    let home = core.db.get_param("HOME").unwrap_or_default();
+    let mut files = vec!["/etc/profile".to_string()];
+    let names = match core.options.query("posix") {
+        true => vec![".profile"],
+        false => vec![".sush_profile", ".bash_profile", ".profile"],
+    };
+
+    for name in names {
+        let path = format!("{home}/{name}");
+        if file_check::is_regular_file(&path) {
+            files.push(path);
+            break;
+        }
+    }
+
+    for f in files {
+        if file_check::is_regular_file(&f) {
+            core.db.exit_status = source::source(core, &[".".to_string(), f]);
+        }
+    }
+
+    core.continue_counter = 0;
+}
+
+fn set_parameters(script_parts: Vec<String>, core: &mut ShellCore, command: &str, s_opt: bool) {
+    match script_parts.is_empty() || s_opt {
         true => {
             core.db.position_parameters[0] = vec![command.to_string()];
+            core.db.position_parameters[0].extend(script_parts);
             core.script_name = "-".to_string();
+            core.db.flags += "s";
         }
         false => {
             core.db.position_parameters[0] = script_parts;
@@ -5,6 +5,7 @@ use crate::core::{builtins, ShellCore};
 use crate::feed_script;
 use crate::feeder::Feeder;
 use crate::parse_and_exec;
+use crate::{read_startup_files, StartupFiles};
 use crate::signal;
 use crate::utils::exit;
 use builtins::option;
@@ -24,7 +25,12 @@ pub fn set_parameters(c_parts: &[String], core: &mut ShellCore, command: &str) {
     }
 }
 
-pub fn run_and_exit(args: &[String], c_parts: &[String], core: &mut ShellCore) {
+pub fn run_and_exit(
+    args: &[String],
+    c_parts: &[String],
+    startup: &StartupFiles,
+    core: &mut ShellCore,
+) {
     let _ = core.configure_c_mode();
 
     if c_parts.is_empty() {
@@ -32,9 +38,11 @@ pub fn run_and_exit(args: &[String], c_parts: &[String], core: &mut ShellCore) {
         process::exit(2);
     }
 
-    signal::run_signal_check(core);
+    signal::init_dispatcher(core);
     core.db.flags.retain(|f| f != 'i');
 
+    read_startup_files(core, startup);
+
     core.db.flags += "c";
     if core.db.flags.contains('v') {
         eprintln!("{}", &c_parts[0]);
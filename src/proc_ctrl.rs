@@ -2,13 +2,14 @@
 //SPDX-License-Identifier: BSD-3-Clause
 
 use crate::error::exec::ExecError;
-use crate::utils::c_string;
+use crate::utils::{c_string, clock};
 use crate::{error, exit, signal, Feeder, Script, ShellCore};
 use nix::errno::Errno;
 use nix::sys::resource::UsageWho;
 use nix::sys::signal::Signal;
 use nix::sys::wait::{WaitPidFlag, WaitStatus};
 use nix::sys::{resource, wait};
+use nix::sys::time::TimeValLike;
 use nix::time::{clock_gettime, ClockId};
 use nix::unistd;
 use nix::unistd::Pid;
@@ -30,7 +31,7 @@ pub fn wait_pipeline(
             core.flip_exit_status();
         }
         close_proc_sub(core);
-        exit::check_e_option(core);
+        check_error_status(core, exclamation);
         return vec![];
     }
 
@@ -73,18 +74,27 @@ pub fn wait_pipeline(
     }
 
     close_proc_sub(core);
-    exit::check_e_option(core);
+    check_error_status(core, exclamation);
 
     ans
 }
 
+fn check_error_status(core: &mut ShellCore, exclamation: bool) {
+    if exclamation {
+        return;
+    }
+
+    signal::run_err_trap(core);
+    exit::check_e_option(core);
+}
+
 fn wait_process(core: &mut ShellCore, child: Pid) -> WaitStatus {
     let waitflags = match core.is_subshell {
         true => None,
         false => Some(WaitPidFlag::WUNTRACED | WaitPidFlag::WCONTINUED),
     };
 
-    let ws = wait::waitpid(child, waitflags);
+    let ws = signal::waitpid(child, waitflags);
 
     core.db.exit_status = match ws {
         Ok(WaitStatus::Exited(_pid, status)) => status,
@@ -140,41 +150,97 @@ pub fn set_pgid(core: &mut ShellCore, pid: Pid, pgid: Pid) {
     }
 }
 
-fn show_time(core: &ShellCore) {
+const DEFAULT_TIMEFORMAT: &str = "\nreal\t%3lR\nuser\t%3lU\nsys\t%3lS";
+const POSIX_TIMEFORMAT: &str = "real %2R\nuser %2U\nsys %2S";
+const NULL_COMMAND_TIMEFORMAT: &str = "user\t%2lU\nsys\t%2lS"; // `time' alone in posix mode
+
+fn expand_timeformat(format: &str, real: i64, user: i64, sys: i64) -> Result<String, char> {
+    let mut ans = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            ans.push(c);
+            continue;
+        }
+
+        let precision = match chars.peek() {
+            None => {
+                ans.push('%');
+                break;
+            }
+            Some('%') => {
+                chars.next();
+                ans.push('%');
+                continue;
+            }
+            Some(d) if d.is_ascii_digit() => {
+                let p = d.to_digit(10).unwrap() as usize;
+                chars.next();
+                p
+            }
+            _ => 3,
+        };
+        let long = chars.next_if_eq(&'l').is_some();
+
+        ans += &match chars.next() {
+            Some('R') => clock::format_usec(real, precision, long),
+            Some('U') => clock::format_usec(user, precision, long),
+            Some('S') => clock::format_usec(sys, precision, long),
+            Some('P') if real > 0 => format!("{:.2}", (user + sys) as f64 * 100.0 / real as f64),
+            Some('P') => "0.00".to_string(),
+            c => return Err(c.unwrap_or('\0')),
+        };
+    }
+
+    Ok(ans)
+}
+
+fn show_time(core: &mut ShellCore) {
     let real_end_time = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
 
     let core_usage = resource::getrusage(UsageWho::RUSAGE_SELF).unwrap();
     let children_usage = resource::getrusage(UsageWho::RUSAGE_CHILDREN).unwrap();
 
     let real_diff = real_end_time - core.measured_time.real;
-    eprintln!(
-        "\nreal\t{}m{}.{:06}s",
-        real_diff.tv_sec() / 60,
-        real_diff.tv_sec() % 60,
-        real_diff.tv_nsec() / 1000
-    );
     let user_diff = core_usage.user_time() + children_usage.user_time() - core.measured_time.user;
-    eprintln!(
-        "user\t{}m{}.{:06}s",
-        user_diff.tv_sec() / 60,
-        user_diff.tv_sec() % 60,
-        user_diff.tv_usec()
-    );
     let sys_diff = core_usage.system_time() + children_usage.system_time() - core.measured_time.sys;
-    eprintln!(
-        "sys \t{}m{}.{:06}s",
-        sys_diff.tv_sec() / 60,
-        sys_diff.tv_sec() % 60,
-        sys_diff.tv_usec()
+
+    let format = match core.measured_time.posix_format {
+        true => POSIX_TIMEFORMAT.to_string(),
+        false => match core.db.exist("TIMEFORMAT") {
+            true => core.db.get_param("TIMEFORMAT").unwrap_or_default(),
+            false if core.measured_time.null_command && core.options.query("posix") => {
+                NULL_COMMAND_TIMEFORMAT.to_string()
+            }
+            false => DEFAULT_TIMEFORMAT.to_string(),
+        },
+    };
+
+    if format.is_empty() {
+        return;
+    }
+
+    let (real, user, sys) = (
+        real_diff.num_microseconds(),
+        user_diff.num_microseconds(),
+        sys_diff.num_microseconds(),
     );
+    match expand_timeformat(&format, real, user, sys) {
+        Ok(s) => eprintln!("{s}"),
+        Err(c) => {
+            let msg = format!("TIMEFORMAT: `{c}': invalid format character");
+            ExecError::Other(msg).print(core);
+        }
+    }
 }
 
 pub fn exec_command(args: &[String], core: &mut ShellCore, fullpath: &str) -> ! {
     let cargs = c_string::to_cargs(args);
     let cfullpath = CString::new(fullpath.to_string()).unwrap();
 
-    if !fullpath.is_empty() {
-        let _ = unistd::execv(&cfullpath, &cargs);
+    if !fullpath.is_empty() && let Err(Errno::ENOENT) = unistd::execv(&cfullpath, &cargs) {
+        exit::no_such_file(fullpath, core);
     }
     let result = unistd::execvp(&cargs[0], &cargs);
 
@@ -2,62 +2,297 @@
 //SPDX-License-Identifier: BSD-3-Clause
 
 extern crate libc;
-use libc::{dup2, close};
 
+use crate::core::builtins::trap;
 use crate::core::ShellCore;
 use crate::feeder::Feeder;
 use crate::Script;
+use nix::errno::Errno;
 use nix::sys::signal;
-use nix::sys::signal::{SigHandler, Signal};
-use signal_hook::consts;
-use signal_hook::iterator::Signals;
+use nix::sys::signal::{SaFlags, SigAction, SigHandler, SigSet, Signal};
+use nix::sys::wait;
+use nix::sys::wait::{WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use std::os::fd::RawFd;
 use std::sync::atomic::Ordering::Relaxed;
-use std::sync::Arc;
-use std::{thread, time};
+use std::sync::atomic::{AtomicBool, AtomicI32};
+use std::sync::{Arc, LazyLock};
+
+/* Every handler writes a byte to this pipe so that the shell can
+ * sleep in poll(2) instead of checking flags periodically. */
+static SIGNAL_PIPE: [AtomicI32; 2] = [AtomicI32::new(-1), AtomicI32::new(-1)];
+pub static SIGINT: LazyLock<Arc<AtomicBool>> = LazyLock::new(|| Arc::new(AtomicBool::new(false)));
+
+const SIGNAL_NUM_MAX: usize = 65;
+static TRAPPED: [AtomicBool; SIGNAL_NUM_MAX] = [const { AtomicBool::new(false) }; SIGNAL_NUM_MAX];
+
+static IGNORED_ON_ENTRY: [AtomicBool; SIGNAL_NUM_MAX] =
+    [const { AtomicBool::new(false) }; SIGNAL_NUM_MAX];
+
+static CHILD_CHANGED: AtomicBool = AtomicBool::new(false);
+
+fn wake_up() {
+    let fd = SIGNAL_PIPE[1].load(Relaxed);
+    if fd >= 0 {
+        let byte = 0u8;
+        unsafe { libc::write(fd, &byte as *const u8 as *const libc::c_void, 1) };
+    }
+}
+
+extern "C" fn on_trapped(sig: libc::c_int) {
+    if let Some(flag) = TRAPPED.get(sig as usize) {
+        flag.store(true, Relaxed);
+    }
+    wake_up();
+}
+
+extern "C" fn on_sigint(_: libc::c_int) {
+    SIGINT.store(true, Relaxed);
+    let msg = b"^C\n";
+    unsafe { libc::write(2, msg.as_ptr() as *const libc::c_void, msg.len()) };
+    wake_up();
+}
+
+extern "C" fn on_sigchld(_: libc::c_int) {
+    CHILD_CHANGED.store(true, Relaxed);
+    wake_up();
+}
+
+pub fn signal_name(n: i32) -> Option<String> {
+    if let Ok(s) = Signal::try_from(n) {
+        return Some(s.to_string());
+    }
+
+    let (min, max) = (libc::SIGRTMIN(), libc::SIGRTMAX());
+    match n {
+        _ if n == min => Some("SIGRTMIN".to_string()),
+        _ if n == max => Some("SIGRTMAX".to_string()),
+        _ if n > min && n <= min + 15 => Some(format!("SIGRTMIN+{}", n - min)),
+        _ if n > min + 15 && n < max => Some(format!("SIGRTMAX-{}", max - n)),
+        _ => None,
+    }
+}
+
+/* accepts names with or without "SIG" in any case and numbers */
+pub fn signal_number(spec: &str) -> Option<i32> {
+    if let Ok(n) = spec.parse::<i32>() {
+        return match n == 0 || signal_name(n).is_some() {
+            true => Some(n),
+            false => None,
+        };
+    }
+
+    let upper = spec.to_uppercase();
+    let name = match upper.starts_with("SIG") {
+        true => upper,
+        false => format!("SIG{upper}"),
+    };
+
+    let max = libc::SIGRTMAX();
+    (1..=max).find(|n| signal_name(*n).as_deref() == Some(&name))
+}
+
+pub fn print_signal_list() {
+    let mut line = String::new();
+    let nums = (1..=libc::SIGRTMAX()).filter(|n| signal_name(*n).is_some());
+    for (i, n) in nums.enumerate() {
+        line += &format!("{:2}) {}\t", n, signal_name(n).unwrap());
+        if i % 5 == 4 {
+            line.pop();
+            println!("{}", &line);
+            line.clear();
+        }
+    }
+    if !line.is_empty() {
+        println!("{}", &line);
+    }
+}
+
+/* Must be called before the shell changes any disposition.
+ * SIGPIPE is skipped since the Rust runtime has already ignored it. */
+pub fn record_entry_dispositions() {
+    for (n, flag) in IGNORED_ON_ENTRY.iter().enumerate().skip(1) {
+        if n as i32 == libc::SIGPIPE {
+            continue;
+        }
+
+        let mut old: libc::sigaction = unsafe { std::mem::zeroed() };
+        if unsafe { libc::sigaction(n as libc::c_int, std::ptr::null(), &mut old) } == 0 {
+            flag.store(old.sa_sigaction == libc::SIG_IGN, Relaxed);
+        }
+    }
+}
+
+pub fn ignored_on_entry(n: i32) -> bool {
+    IGNORED_ON_ENTRY.get(n as usize).is_some_and(|f| f.load(Relaxed))
+}
 
 pub fn ignore(sig: Signal) {
     unsafe { signal::signal(sig, SigHandler::SigIgn) }.expect("sush(fatal): cannot ignore signal");
 }
 
+/* gives back the disposition at the start of the shell */
 pub fn restore(sig: Signal) {
-    unsafe { signal::signal(sig, SigHandler::SigDfl) }.expect("sush(fatal): cannot restore signal");
+    let handler = match ignored_on_entry(sig as i32) {
+        true => SigHandler::SigIgn,
+        false => SigHandler::SigDfl,
+    };
+    unsafe { signal::signal(sig, handler) }.expect("sush(fatal): cannot restore signal");
 }
 
-pub fn run_signal_check(core: &mut ShellCore) {
-    for fd in 3..10 {
-        //use FD 3~9 to prevent signal-hool from using these FDs
-            let _ = unsafe{dup2(2, fd)};
+fn sigaction(sig: Signal, handler: SigHandler) -> Result<SigAction, Errno> {
+    let action = SigAction::new(handler, SaFlags::SA_RESTART, SigSet::empty());
+    unsafe { signal::sigaction(sig, &action) }
+}
+
+/* libc is used for traps since nix::Signal can't express real-time signals */
+fn set_handler(n: i32, handler: libc::sighandler_t) -> Option<libc::sigaction> {
+    let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+    let mut old: libc::sigaction = unsafe { std::mem::zeroed() };
+    action.sa_sigaction = handler;
+    action.sa_flags = libc::SA_RESTART;
+    unsafe { libc::sigemptyset(&mut action.sa_mask) };
+
+    match unsafe { libc::sigaction(n, &action, &mut old) } {
+        0 => Some(old),
+        _ => None,
     }
+}
+
+fn set_action(core: &mut ShellCore, n: i32, handler: libc::sighandler_t) {
+    if let Some(old) = set_handler(n, handler) {
+        core.trap_originals.entry(n).or_insert(old);
+    }
+}
+
+pub fn catch(core: &mut ShellCore, n: i32) {
+    let handler = on_trapped as extern "C" fn(libc::c_int);
+    set_action(core, n, handler as libc::sighandler_t);
+}
+
+pub fn ignore_by_trap(core: &mut ShellCore, n: i32) {
+    set_action(core, n, libc::SIG_IGN);
+}
 
-    core.sigint.store(true, Relaxed);
-    let sigint = Arc::clone(&core.sigint);
+pub fn reset_trap(core: &mut ShellCore, n: i32) {
+    if let Some(old) = core.trap_originals.remove(&n) {
+        let _ = unsafe { libc::sigaction(n, &old, std::ptr::null_mut()) };
+    }
+}
 
-    thread::spawn(move || {
-        let mut signals =
-            Signals::new(vec![consts::SIGINT]).expect("sush(fatal): cannot prepare signal data");
+pub fn reset_traps_in_subshell(core: &mut ShellCore) {
+    core.parent_traps = Some(trap::trap_entries(core));
 
-        for fd in 3..10 {
-            // release FD 3~9
-            //nix::unistd::close(fd).expect("sush(fatal): init error");
-            let _ = unsafe{close(fd)};
+    for (n, script) in std::mem::take(&mut core.traps) {
+        if n == 0 || n >= trap::DEBUG_TRAP {
+            core.traps.insert(n, script); // ignored pseudo signals
+            continue;
         }
-        sigint.store(false, Relaxed);
-
-        loop {
-            thread::sleep(time::Duration::from_millis(100)); //0.1秒周期に変更
-            for signal in signals.pending() {
-                if signal == consts::SIGINT {
-                    sigint.store(true, Relaxed);
-                    eprintln!("^C");
-                }
-            }
+
+        if script.is_empty() {
+            let _ = set_handler(n, libc::SIG_IGN);
+            core.traps.insert(n, script);
+        } else {
+            let handler = match ignored_on_entry(n) {
+                true => libc::SIG_IGN,
+                false => libc::SIG_DFL,
+            };
+            let _ = set_handler(n, handler);
+            core.trap_originals.remove(&n);
         }
-    });
+    }
+}
+
+pub fn take_child_change() -> bool {
+    CHILD_CHANGED.swap(false, Relaxed)
+}
 
-    while core.sigint.load(Relaxed) {
-        thread::sleep(time::Duration::from_millis(1));
+pub fn is_trapped() -> bool {
+    TRAPPED.iter().any(|t| t.load(Relaxed))
+}
+
+fn open_signal_pipe(core: &mut ShellCore) {
+    let (recv, send) = core.fds.pipe();
+    for (i, fd) in [recv, send].into_iter().enumerate() {
+        /* keep FDs 0~9 free for users */
+        let high = core.fds.dupfd_cloexec(fd, 10).expect("sush(fatal): cannot prepare signal pipe");
+        core.fds.close(fd);
+        unsafe { libc::fcntl(high, libc::F_SETFL, libc::O_NONBLOCK) };
+        SIGNAL_PIPE[i].store(high, Relaxed);
     }
-} //thanks: https://dev.to/talzvon/handling-unix-kill-signals-in-rust-55g6
+}
+
+pub fn init_dispatcher(core: &mut ShellCore) {
+    open_signal_pipe(core);
+
+    sigaction(Signal::SIGINT, SigHandler::Handler(on_sigint))
+        .expect("sush(fatal): cannot set SIGINT handler");
+    sigaction(Signal::SIGCHLD, SigHandler::Handler(on_sigchld))
+        .expect("sush(fatal): cannot set SIGCHLD handler");
+}
+
+/* A forked child gets its own pipe. Otherwise it can read
+ * wakeups written for the parent and the parent sleeps forever. */
+pub fn init_dispatcher_in_subshell(core: &mut ShellCore) {
+    if SIGNAL_PIPE[0].load(Relaxed) < 0 {
+        return;
+    }
+
+    for fd in &SIGNAL_PIPE {
+        core.fds.close(fd.swap(-1, Relaxed));
+    }
+    open_signal_pipe(core);
+
+    sigaction(Signal::SIGCHLD, SigHandler::Handler(on_sigchld))
+        .expect("sush(fatal): cannot set SIGCHLD handler");
+}
+
+fn drain_signal_pipe(fd: RawFd) {
+    let mut buf = [0u8; 64];
+    while unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) } > 0 {}
+}
+
+/* Sleeps until a signal arrives or `fd` becomes readable.
+ * Returns true if `fd` is readable. */
+pub fn wait_event(fd: Option<RawFd>) -> bool {
+    wait_event_timeout(fd, -1)
+}
+
+/* same as wait_event but gives up after `timeout` milliseconds
+ * (no limit when negative) */
+pub fn wait_event_timeout(fd: Option<RawFd>, timeout: i32) -> bool {
+    let sig_fd = SIGNAL_PIPE[0].load(Relaxed);
+    let mut fds = vec![libc::pollfd { fd: sig_fd, events: libc::POLLIN, revents: 0 }];
+    if let Some(fd) = fd {
+        fds.push(libc::pollfd { fd, events: libc::POLLIN, revents: 0 });
+    }
+
+    let n = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout) };
+    if n <= 0 {
+        return false;
+    }
+
+    if fds[0].revents & libc::POLLIN != 0 {
+        drain_signal_pipe(sig_fd);
+    }
+    fds.len() > 1 && fds[1].revents != 0
+}
+
+pub fn waitpid(pid: Pid, flags: Option<WaitPidFlag>) -> Result<WaitStatus, Errno> {
+    if SIGNAL_PIPE[0].load(Relaxed) < 0 {
+        return wait::waitpid(pid, flags);
+    }
+
+    let flags = flags.unwrap_or(WaitPidFlag::empty()) | WaitPidFlag::WNOHANG;
+    loop {
+        match wait::waitpid(pid, Some(flags))? {
+            WaitStatus::StillAlive => {
+                wait_event(None);
+            }
+            ws => return Ok(ws),
+        }
+    }
+}
 
 pub fn input_interrupt_check(feeder: &mut Feeder, core: &mut ShellCore) -> bool {
     if !core.sigint.load(Relaxed) {
@@ -75,30 +310,92 @@ pub fn check_trap(core: &mut ShellCore) {
     let bkup = core.db.exit_status;
 
     let mut scripts = vec![];
-    for t in &core.trapped {
-        if t.0.load(Relaxed) {
-            scripts.push(t.1.clone());
-            t.0.store(false, Relaxed);
+    for (n, script) in &core.traps {
+        let Some(flag) = TRAPPED.get(*n as usize) else {
+            continue;
+        };
+        let trapped = flag.swap(false, Relaxed);
+        if trapped && !script.is_empty() {
+            scripts.push(script.clone());
         }
     }
 
     for s in scripts {
-        let mut feeder = Feeder::new(&s);
-        let mut script = match Script::parse(&mut feeder, core, true) {
-            Ok(None) => {
-                continue;
-            }
-            Ok(s) => s.unwrap(),
-            Err(e) => {
-                e.print(core);
-                continue;
-            }
-        };
+        run_trap_script(core, &s);
+    }
 
-        if let Err(e) = script.exec(core) {
-            e.print(core);
-        }
+    core.db.exit_status = bkup;
+}
+
+pub fn run_err_trap(core: &mut ShellCore) {
+    if core.err_script.is_empty()
+        || core.err_trap_running
+        || core.suspend_e_option
+        || core.db.exit_status == 0
+    {
+        return;
+    }
+
+    let bkup = core.db.exit_status;
+    let script = core.err_script.clone();
+    core.err_trap_running = true;
+    run_trap_script(core, &script);
+    core.err_trap_running = false;
+    core.db.exit_status = bkup;
+}
+
+pub fn run_debug_trap(core: &mut ShellCore, command: &str) -> bool {
+    if core.debug_script.is_empty() || core.debug_trap_running {
+        return true;
+    }
+
+    let _ = core.db.set_param("BASH_COMMAND", command, None);
+    let bkup = core.db.exit_status;
+    let script = core.debug_script.clone();
+    core.debug_trap_running = true;
+    run_trap_script(core, &script);
+    core.debug_trap_running = false;
+    let status = core.db.exit_status;
+    core.db.exit_status = bkup;
+
+    if !core.shopts.query("extdebug") {
+        return true;
+    }
+    if status == 2 && core.source_function_level > 0 {
+        core.return_flag = true;
+    }
+    status == 0
+}
+
+pub fn run_return_trap(core: &mut ShellCore) {
+    if core.return_script.is_empty() || core.return_trap_running {
+        return;
     }
 
+    let bkup = core.db.exit_status;
+    let script = core.return_script.clone();
+    core.return_trap_running = true;
+    run_trap_script(core, &script);
+    core.return_trap_running = false;
+    core.return_flag = false;
     core.db.exit_status = bkup;
 }
+
+fn run_trap_script(core: &mut ShellCore, s: &str) {
+    let mut feeder = Feeder::new(s);
+    if let Ok(n) = core.db.get_param("LINENO").unwrap_or_default().parse::<usize>() {
+        feeder.lineno = n.max(1);
+    }
+    let mut script = match Script::parse(&mut feeder, core, true) {
+        Ok(Some(s)) => s,
+        Ok(None) => return,
+        Err(e) => {
+            e.print(core);
+            return;
+        }
+    };
+
+    if let Err(e) = script.exec(core) {
+        e.print(core);
+    }
+}
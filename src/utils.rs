@@ -22,26 +22,13 @@ use io_streams::StreamReader;
 use std::io::Read;
 use std::path::Path;
 
+pub const RESERVED_WORDS: [&str; 17] = [
+    "[[", "]]", "{", "}", "while", "for", "do", "done", "if", "then", "elif", "else", "fi", "case",
+    "coproc", "esac", "repeat",
+];
+
 pub fn reserved(w: &str) -> bool {
-    matches!(
-        w,
-        "[[" | "]]"
-            | "{"
-            | "}"
-            | "while"
-            | "for"
-            | "do"
-            | "done"
-            | "if"
-            | "then"
-            | "elif"
-            | "else"
-            | "fi"
-            | "case"
-            | "coproc"
-            | "esac"
-            | "repeat"
-    )
+    RESERVED_WORDS.contains(&w)
 }
 
 pub fn split_words(s: &str) -> Vec<String> {
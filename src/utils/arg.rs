@@ -28,13 +28,6 @@ pub fn consume_option(option: &str, args: &mut Vec<String>) -> bool {
     false
 }
 
-pub fn consume_starts_with(s: &str, args: &mut Vec<String>) -> Vec<String> {
-    let mut ans = args.clone();
-    ans.retain(|a| a.starts_with(s));
-    args.retain(|a| !a.starts_with(s));
-    ans
-}
-
 pub fn consume_with_next_arg(prev_opt: &str, args: &mut Vec<String>) -> Option<String> {
     match args.iter().position(|a| a == prev_opt) {
         Some(pos) => match pos + 1 >= args.len() {
@@ -96,11 +89,25 @@ pub fn dissolve_options(args: &[String]) -> Vec<String> {
     ans
 }
 
+pub fn takes_next_arg(opt: &str) -> bool {
+    ["-o", "+o", "-O", "+O", "--rcfile", "--init-file"].contains(&opt)
+}
+
 pub fn dissolve_options_main() -> Vec<String> {
     let mut ans = vec![];
     let mut stop = false;
     for (i, a) in std::env::args().enumerate() {
-        if i != 0 && !a.starts_with("-") || a == "--" {
+        if i == 0 {
+            ans.push(a); // argv[0] may start with '-' for a login shell
+            continue;
+        }
+
+        if !stop && ans.last().is_some_and(|o: &String| takes_next_arg(o)) {
+            ans.push(a);
+            continue;
+        }
+
+        if !a.starts_with("-") || a == "--" {
             stop = true;
         }
 
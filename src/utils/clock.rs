@@ -36,12 +36,31 @@ pub fn get_seconds(v: &mut Vec<String>) -> String {
 }
 */
 
-pub fn get_epochseconds() -> String {
+pub fn epoch_seconds() -> i64 {
     let real = time::clock_gettime(ClockId::CLOCK_REALTIME).unwrap();
-    real.tv_sec().to_string()
+    real.tv_sec()
+}
+
+pub fn get_epochseconds() -> String {
+    epoch_seconds().to_string()
 }
 
 pub fn get_epochrealtime() -> String {
     let real = time::clock_gettime(ClockId::CLOCK_REALTIME).unwrap();
     format!("{}.{:06}", real.tv_sec(), real.tv_nsec() / 1000).to_string()
 }
+
+/* bash style time representation used by TIMEFORMAT and times */
+pub fn format_usec(usec: i64, precision: usize, long: bool) -> String {
+    let usec = usec.max(0);
+    let (sec, frac) = (usec / 1_000_000, usec % 1_000_000);
+    let frac = match precision.min(3) {
+        0 => String::new(),
+        p => format!(".{}", &format!("{frac:06}")[..p]),
+    };
+
+    match long {
+        true => format!("{}m{}{}s", sec / 60, sec % 60, frac),
+        false => format!("{sec}{frac}"),
+    }
+}
@@ -93,3 +93,61 @@ pub fn glob(dir: &str, pattern: &str, shopts: &Options) -> Vec<String> {
     ans.dedup();
     ans
 }
+
+/* 0: same, 1: two characters are transposed,
+ * 2: one character is changed, added or removed, 3: others */
+fn spell_distance(cand: &[char], guess: &[char]) -> usize {
+    let n = cand.iter().zip(guess).take_while(|(a, b)| a == b).count();
+    let (c, g) = (&cand[n..], &guess[n..]);
+
+    if c.is_empty() && g.is_empty() {
+        0
+    } else if c.len() >= 2 && g.len() >= 2 && c[0] == g[1] && c[1] == g[0] && c[2..] == g[2..] {
+        1
+    } else if (!c.is_empty() && !g.is_empty() && c[1..] == g[1..])
+        || (!c.is_empty() && c[1..] == *g)
+        || (!g.is_empty() && *c == g[1..])
+    {
+        2
+    } else {
+        3
+    }
+}
+
+fn closest_file(dir: &str, guess: &str) -> Option<String> {
+    let guess: Vec<char> = guess.chars().collect();
+    let mut ans = None;
+    let mut min = 3;
+
+    for f in files(dir) {
+        let d = spell_distance(&f.chars().collect::<Vec<char>>(), &guess);
+        if d < min {
+            (ans, min) = (Some(f), d);
+        }
+        if min == 0 {
+            break;
+        }
+    }
+    ans
+}
+
+/* corrects every component of the path like dirspell of Bash */
+pub fn correct_spelling(path: &str) -> Option<String> {
+    let mut ans = String::new();
+
+    for (i, comp) in path.split('/').enumerate() {
+        if i > 0 {
+            ans.push('/');
+        }
+
+        match comp {
+            "" | "." | ".." => ans += comp,
+            _ => ans += &closest_file(&ans, comp)?,
+        }
+    }
+
+    match ans == path {
+        true => None,
+        false => Some(ans),
+    }
+}
@@ -1,17 +1,36 @@
 //SPDX-FileCopyrightText: 2024 Ryuichi Ueda ryuichiueda@gmail.com
 //SPDX-License-Identifier: BSD-3-Clause
 
-use crate::{Feeder, Script, ShellCore};
+use crate::core::builtins::source;
+use crate::{file_check, Feeder, Script, ShellCore};
 use crate::utils::ExecError;
 use std::process;
 
 pub fn normal(core: &mut ShellCore) -> ! {
+    if core.db.flags.contains('i') {
+        run_logout_file(core);
+    }
     run_script(core);
 
     core.write_history_to_file();
     process::exit(core.db.exit_status % 256)
 }
 
+pub fn run_logout_file(core: &mut ShellCore) {
+    if core.logout_file_run || core.is_subshell || !core.shopts.query("login_shell") {
+        return;
+    }
+
+    core.logout_file_run = true;
+    let home = core.db.get_param("HOME").unwrap_or_default();
+    let file = home + "/.sush_logout";
+    if file_check::is_regular_file(&file) {
+        let es = core.db.exit_status;
+        source::source(core, &[".".to_string(), file]);
+        core.db.exit_status = es;
+    }
+}
+
 fn run_script(core: &mut ShellCore) {
     if core.exit_script_run {
         return;
@@ -59,6 +78,12 @@ pub fn not_found(command_name: &str, core: &mut ShellCore) -> ! {
     process::exit(127)
 }
 
+pub fn no_such_file(path: &str, core: &mut ShellCore) -> ! {
+    let msg = format!("{path}: No such file or directory");
+    ExecError::Other(msg).print(core);
+    process::exit(127)
+}
+
 pub fn internal(s: &str) -> ! {
     panic!("SUSH INTERNAL ERROR: {s}")
 }
@@ -3,10 +3,8 @@
 //SPDX-License-Identifier: BSD-3-Clause
 
 use crate::utils::file_check;
-use crate::ShellCore;
-use std::env;
-use std::ffi::OsString;
-use std::path::{Component, Path, PathBuf};
+use std::ffi::{c_char, CStr, OsString};
+use std::path::Path;
 
 pub fn oss_to_name(oss: &OsString) -> String {
     oss.to_string_lossy().to_string()
@@ -16,62 +14,28 @@ pub fn buf_to_name(path: &Path) -> String {
     path.to_string_lossy().to_string()
 }
 
-pub fn search_command(command: &str) -> Option<String> {
-    let paths = env::var_os("PATH");
-    paths.as_ref()?;
-
-    for path in env::split_paths(&paths.unwrap()) {
-        let compath = buf_to_name(&path) + "/" + command;
-        if file_check::exists(&compath) {
-            return Some(compath);
-        }
-    }
-
-    None
+/* every executable file named COMMAND in the colon separated PATHS */
+pub fn search_commands(command: &str, paths: &str) -> Vec<String> {
+    paths
+        .split(':')
+        .map(|p| match p.is_empty() {
+            true => format!("./{command}"),
+            false => format!("{}/{command}", p.trim_end_matches('/')),
+        })
+        .filter(|f| file_check::is_executable(f) && !file_check::is_dir(f))
+        .collect()
 }
 
-pub fn make_absolute_path(core: &mut ShellCore, path_str: &str) -> PathBuf {
-    let path = Path::new(&path_str);
-    let mut absolute = PathBuf::new();
-    if !path.is_relative() {
-        absolute.push(path);
-        return absolute;
-    }
-
-    if path.starts_with("~") {
-        // tilde -> $HOME
-        let home_dir = core.db.get_param("HOME").unwrap_or_default();
-        if !home_dir.is_empty() {
-            absolute.push(PathBuf::from(home_dir));
-            let num = match path_str.len() > 1 && path_str.starts_with("~/") {
-                true => 2,
-                false => 1,
-            };
-            absolute.push(PathBuf::from(&path_str[num..]));
-        }
-    } else {
-        // current
-        if let Some(tcwd) = core.get_current_directory() {
-            absolute.push(tcwd);
-            absolute.push(path);
-        };
+/* the PATH which finds all the standard utilities, like confstr(_CS_PATH) */
+pub fn standard_path() -> String {
+    let len = unsafe { libc::confstr(libc::_CS_PATH, std::ptr::null_mut(), 0) };
+    if len == 0 {
+        return "/bin:/usr/bin".to_string();
     }
 
-    absolute
-}
-
-pub fn make_canonical_path(core: &mut ShellCore, path_str: &str) -> PathBuf {
-    let path = make_absolute_path(core, path_str);
-    let mut canonical = PathBuf::new();
-    for component in path.components() {
-        match component {
-            Component::RootDir => canonical.push(Component::RootDir),
-            Component::ParentDir => {
-                canonical.pop();
-            }
-            Component::Normal(c) => canonical.push(c),
-            _ => (),
-        }
-    }
-    canonical
+    let mut buf = vec![0 as c_char; len];
+    unsafe { libc::confstr(libc::_CS_PATH, buf.as_mut_ptr(), len) };
+    unsafe { CStr::from_ptr(buf.as_ptr()) }
+        .to_string_lossy()
+        .to_string()
 }
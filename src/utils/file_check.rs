@@ -66,7 +66,7 @@ pub fn metadata_check(name: &str, tp: &str) -> bool {
         "-b" => return meta.file_type().is_block_device(),
         "-c" => return meta.file_type().is_char_device(),
         "-p" => return meta.file_type().is_fifo(),
-        "-s" => return meta.len() == 0,
+        "-s" => return meta.len() > 0,
         "-G" => return unistd::getgid() == meta.st_gid().into(),
         "-N" => {
             let modified_time = match meta.modified() {